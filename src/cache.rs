@@ -0,0 +1,87 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::types::{PackageJSON, TSConfig};
+
+/// Caches shared by every call to [`crate::Resolver::resolve`].
+///
+/// Only successful lookups are cached. A package.json or tsconfig.json that fails
+/// to parse is read again next time, so that the error can be reported again.
+#[derive(Debug, Default)]
+pub struct ResolverCache {
+    is_file: RwLock<HashMap<PathBuf, bool>>,
+    is_dir: RwLock<HashMap<PathBuf, bool>>,
+    package_json: RwLock<HashMap<PathBuf, Option<Arc<PackageJSON>>>>,
+    tsconfig: RwLock<HashMap<PathBuf, Option<Arc<TSConfig>>>>,
+    canonicalize: RwLock<HashMap<PathBuf, PathBuf>>,
+}
+
+impl ResolverCache {
+    pub fn is_file(&self, path: &Path, load: impl FnOnce() -> bool) -> bool {
+        get_or_insert_with(&self.is_file, path, || Ok::<_, ()>(load())).unwrap_or(false)
+    }
+
+    pub fn is_dir(&self, path: &Path, load: impl FnOnce() -> bool) -> bool {
+        get_or_insert_with(&self.is_dir, path, || Ok::<_, ()>(load())).unwrap_or(false)
+    }
+
+    pub fn package_json<E>(
+        &self,
+        path: &Path,
+        load: impl FnOnce() -> Result<Option<PackageJSON>, E>,
+    ) -> Result<Option<Arc<PackageJSON>>, E> {
+        get_or_insert_with(&self.package_json, path, || Ok(load()?.map(Arc::new)))
+    }
+
+    pub fn tsconfig<E>(
+        &self,
+        path: &Path,
+        load: impl FnOnce() -> Result<Option<TSConfig>, E>,
+    ) -> Result<Option<Arc<TSConfig>>, E> {
+        get_or_insert_with(&self.tsconfig, path, || Ok(load()?.map(Arc::new)))
+    }
+
+    pub fn canonicalize<E>(
+        &self,
+        path: &Path,
+        load: impl FnOnce() -> Result<PathBuf, E>,
+    ) -> Result<PathBuf, E> {
+        get_or_insert_with(&self.canonicalize, path, load)
+    }
+
+    pub fn clear(&self) {
+        write(&self.is_file).clear();
+        write(&self.is_dir).clear();
+        write(&self.package_json).clear();
+        write(&self.tsconfig).clear();
+        write(&self.canonicalize).clear();
+    }
+}
+
+fn get_or_insert_with<V: Clone, E>(
+    map: &RwLock<HashMap<PathBuf, V>>,
+    path: &Path,
+    load: impl FnOnce() -> Result<V, E>,
+) -> Result<V, E> {
+    if let Some(value) = read(map).get(path) {
+        return Ok(value.clone());
+    }
+
+    let value = load()?;
+    write(map).insert(path.to_path_buf(), value.clone());
+
+    Ok(value)
+}
+
+// A panic while holding the lock cannot leave a half-written entry behind,
+// so a poisoned cache is still safe to use.
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| e.into_inner())
+}
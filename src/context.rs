@@ -0,0 +1,68 @@
+use crate::{
+    trace::{ResolveTrace, TraceStep},
+    types::{MatchedBy, ResolveKind},
+};
+
+/// The state of a single call to [`crate::Resolver::resolve`], passed along every step.
+#[derive(Debug, Default)]
+pub struct ResolveContext {
+    /// How the file is found. A step records itself after an inner step succeeds, so the
+    /// outermost mechanism wins, e.g. `exports` over the extension probing it relies on.
+    pub matched_by: Option<MatchedBy>,
+    /// `None` unless [`crate::Resolver::resolve_with_trace`] is called.
+    pub trace: Option<ResolveTrace>,
+    /// `None` unless [`crate::Resolver::resolve_with_kind`] is called.
+    pub kind: Option<ResolveKind>,
+    /// The indices of [`crate::EsResolveOptions::alias`] applied to get the specifier being resolved,
+    /// which are not applied again.
+    pub aliased: Vec<usize>,
+    /// The same for [`crate::EsResolveOptions::fallback`].
+    pub fallen_back: Vec<usize>,
+    /// Whether the specifier being resolved is a target of [`crate::EsResolveOptions::module_name_mapper`],
+    /// which is not mapped again.
+    pub mapped: bool,
+    /// Whether the specifier being resolved is a polyfill of [`crate::BrowserBuiltins::Polyfill`],
+    /// which is not polyfilled again.
+    pub polyfilled: bool,
+    /// Whether the specifier being resolved is the `extends` of a tsconfig.json, which is found
+    /// the same way whatever kind of import loads the tsconfig.
+    pub tsconfig: bool,
+}
+
+impl ResolveContext {
+    pub fn with_trace() -> Self {
+        Self {
+            trace: Some(ResolveTrace::default()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_kind(kind: Option<ResolveKind>) -> Self {
+        Self {
+            kind,
+            ..Self::default()
+        }
+    }
+
+    pub fn for_tsconfig() -> Self {
+        Self {
+            tsconfig: true,
+            ..Self::default()
+        }
+    }
+
+    /// [`ResolveContext::fallen_back`] if `fallback`, or else [`ResolveContext::aliased`].
+    pub fn applied_aliases(&mut self, fallback: bool) -> &mut Vec<usize> {
+        match fallback {
+            true => &mut self.fallen_back,
+            false => &mut self.aliased,
+        }
+    }
+
+    /// Record a step, which is only built when tracing.
+    pub fn trace(&mut self, step: impl FnOnce() -> TraceStep) {
+        if let Some(ref mut trace) = self.trace {
+            trace.push(step());
+        }
+    }
+}
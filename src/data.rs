@@ -1,75 +1,127 @@
-use crate::types::Extensions;
+use crate::types::{Extensions, NodeVersion};
 
+/// The Node built-in modules and the versions they are added in, or `(0, 0, 0)` for the ones
+/// Node has always had. Backports to older release lines are not taken into account.
+///
 /// Generated by:
 /// node --experimental-wasi-unstable-preview1 -p "JSON.stringify([...require('module').builtinModules].sort())"
-pub static NODE_CORE_MODULES: &[&str] = &[
-    "_http_agent",
-    "_http_client",
-    "_http_common",
-    "_http_incoming",
-    "_http_outgoing",
-    "_http_server",
-    "_stream_duplex",
-    "_stream_passthrough",
-    "_stream_readable",
-    "_stream_transform",
-    "_stream_wrap",
-    "_stream_writable",
-    "_tls_common",
-    "_tls_wrap",
-    "assert",
-    "assert/strict",
-    "async_hooks",
-    "buffer",
-    "child_process",
-    "cluster",
-    "console",
-    "constants",
-    "crypto",
-    "dgram",
-    "diagnostics_channel",
-    "dns",
-    "dns/promises",
-    "domain",
-    "events",
-    "fs",
-    "fs/promises",
-    "http",
-    "http2",
-    "https",
-    "inspector",
-    "module",
-    "net",
-    "os",
-    "path",
-    "path/posix",
-    "path/win32",
-    "perf_hooks",
-    "process",
-    "punycode",
-    "querystring",
-    "readline",
-    "readline/promises",
-    "repl",
-    "stream",
-    "stream/consumers",
-    "stream/promises",
-    "stream/web",
-    "string_decoder",
-    "sys",
-    "timers",
-    "timers/promises",
-    "tls",
-    "trace_events",
-    "tty",
-    "url",
-    "util",
-    "util/types",
-    "v8",
-    "vm",
-    "wasi",
-    "worker_threads",
-    "zlib",
+/// with the versions from the history of each module in the docs of Node.
+pub static NODE_CORE_MODULES: &[(&str, NodeVersion)] = &[
+    ("_http_agent", (0, 0, 0)),
+    ("_http_client", (0, 0, 0)),
+    ("_http_common", (0, 0, 0)),
+    ("_http_incoming", (0, 0, 0)),
+    ("_http_outgoing", (0, 0, 0)),
+    ("_http_server", (0, 0, 0)),
+    ("_stream_duplex", (0, 0, 0)),
+    ("_stream_passthrough", (0, 0, 0)),
+    ("_stream_readable", (0, 0, 0)),
+    ("_stream_transform", (0, 0, 0)),
+    ("_stream_wrap", (0, 0, 0)),
+    ("_stream_writable", (0, 0, 0)),
+    ("_tls_common", (0, 0, 0)),
+    ("_tls_wrap", (0, 0, 0)),
+    ("assert", (0, 0, 0)),
+    ("assert/strict", (15, 0, 0)),
+    ("async_hooks", (8, 1, 0)),
+    ("buffer", (0, 0, 0)),
+    ("child_process", (0, 0, 0)),
+    ("cluster", (0, 0, 0)),
+    ("console", (0, 0, 0)),
+    ("constants", (0, 0, 0)),
+    ("crypto", (0, 0, 0)),
+    ("dgram", (0, 0, 0)),
+    ("diagnostics_channel", (15, 1, 0)),
+    ("dns", (0, 0, 0)),
+    ("dns/promises", (15, 0, 0)),
+    ("domain", (0, 0, 0)),
+    ("events", (0, 0, 0)),
+    ("fs", (0, 0, 0)),
+    ("fs/promises", (14, 0, 0)),
+    ("http", (0, 0, 0)),
+    ("http2", (8, 4, 0)),
+    ("https", (0, 0, 0)),
+    ("inspector", (8, 0, 0)),
+    ("inspector/promises", (19, 0, 0)),
+    ("module", (0, 0, 0)),
+    ("net", (0, 0, 0)),
+    ("os", (0, 0, 0)),
+    ("path", (0, 0, 0)),
+    ("path/posix", (15, 3, 0)),
+    ("path/win32", (15, 3, 0)),
+    ("perf_hooks", (8, 5, 0)),
+    ("process", (0, 0, 0)),
+    ("punycode", (0, 0, 0)),
+    ("querystring", (0, 0, 0)),
+    ("readline", (0, 0, 0)),
+    ("readline/promises", (17, 0, 0)),
+    ("repl", (0, 0, 0)),
+    ("stream", (0, 0, 0)),
+    ("stream/consumers", (16, 7, 0)),
+    ("stream/promises", (15, 0, 0)),
+    ("stream/web", (16, 5, 0)),
+    ("string_decoder", (0, 0, 0)),
+    ("sys", (0, 0, 0)),
+    ("timers", (0, 0, 0)),
+    ("timers/promises", (15, 0, 0)),
+    ("tls", (0, 0, 0)),
+    ("trace_events", (10, 0, 0)),
+    ("tty", (0, 0, 0)),
+    ("url", (0, 0, 0)),
+    ("util", (0, 0, 0)),
+    ("util/types", (15, 3, 0)),
+    ("v8", (0, 0, 0)),
+    ("vm", (0, 0, 0)),
+    ("wasi", (13, 3, 0)),
+    ("worker_threads", (11, 7, 0)),
+    ("zlib", (0, 0, 0)),
+];
+
+/// The Node built-in modules that are only available with the `node:` prefix, like `node:test`,
+/// as opposed to the `test` package.
+pub static NODE_PREFIX_ONLY_MODULES: &[(&str, NodeVersion)] = &[
+    ("sea", (21, 7, 0)),
+    ("sqlite", (22, 5, 0)),
+    ("test", (18, 0, 0)),
+    ("test/reporters", (19, 9, 0)),
+];
+
+/// The packages that implement Node built-in modules for the browser, or `None` for an empty module,
+/// following [node-libs-browser](https://github.com/webpack/node-libs-browser) of webpack 4.
+pub static NODE_POLYFILLS: &[(&str, Option<&str>)] = &[
+    ("assert", Some("assert")),
+    ("buffer", Some("buffer")),
+    ("child_process", None),
+    ("cluster", None),
+    ("console", Some("console-browserify")),
+    ("constants", Some("constants-browserify")),
+    ("crypto", Some("crypto-browserify")),
+    ("dgram", None),
+    ("dns", None),
+    ("domain", Some("domain-browser")),
+    ("events", Some("events")),
+    ("fs", None),
+    ("http", Some("stream-http")),
+    ("https", Some("https-browserify")),
+    ("module", None),
+    ("net", None),
+    ("os", Some("os-browserify/browser.js")),
+    ("path", Some("path-browserify")),
+    ("process", Some("process/browser.js")),
+    ("punycode", Some("punycode")),
+    ("querystring", Some("querystring-es3")),
+    ("readline", None),
+    ("repl", None),
+    ("stream", Some("stream-browserify")),
+    ("string_decoder", Some("string_decoder")),
+    ("sys", Some("util")),
+    ("timers", Some("timers-browserify")),
+    ("tls", None),
+    ("tty", Some("tty-browserify")),
+    ("url", Some("url")),
+    ("util", Some("util")),
+    ("vm", Some("vm-browserify")),
+    ("zlib", Some("browserify-zlib")),
 ];
 
 pub static DEFAULT_EXTENSIONS: &[Extensions] = &[
@@ -81,11 +133,26 @@ pub static DEFAULT_EXTENSIONS: &[Extensions] = &[
     Extensions::Json,
 ];
 
-pub static REWRITTEN_EXTENSIONS: &[(Extensions, &[Extensions])] = &[
+/// The TypeScript sources that a `.js` specifier, for example, is compiled from.
+pub static TYPESCRIPT_EXTENSION_ALIAS: &[(Extensions, &[Extensions])] = &[
     (Extensions::Js, &[Extensions::Ts, Extensions::Tsx]),
     (Extensions::Jsx, &[Extensions::Ts, Extensions::Tsx]),
     (Extensions::Mjs, &[Extensions::Mts]),
-    (Extensions::Cts, &[Extensions::Cts]),
+    (Extensions::Cjs, &[Extensions::Cts]),
+];
+
+/// Like [`TYPESCRIPT_EXTENSION_ALIAS`], followed by the declaration files.
+pub static TYPESCRIPT_DECLARATION_EXTENSION_ALIAS: &[(Extensions, &[Extensions])] = &[
+    (
+        Extensions::Js,
+        &[Extensions::Ts, Extensions::Tsx, Extensions::Dts],
+    ),
+    (
+        Extensions::Jsx,
+        &[Extensions::Ts, Extensions::Tsx, Extensions::Dts],
+    ),
+    (Extensions::Mjs, &[Extensions::Mts, Extensions::Dmts]),
+    (Extensions::Cjs, &[Extensions::Cts, Extensions::Dcts]),
 ];
 
 pub static PACKAGE_JSON: &str = "package.json";
@@ -0,0 +1,135 @@
+use std::{
+    fmt::Debug,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// What the resolver needs to know about a path.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FileMetadata {
+    pub is_file: bool,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+}
+
+/// All I/O done by [`crate::Resolver`] goes through this trait, so that modules can be
+/// resolved against editor buffers, virtual trees, archives, etc.
+///
+/// [`OsFileSystem`] is the default implementation, backed by [`std::fs`].
+pub trait FileSystem: Debug + Send + Sync {
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Like [`std::fs::metadata`], symbolic links are followed.
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata>;
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Returns the absolute path with all symbolic links resolved.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// The paths of the entries in the directory `path`, in no particular order.
+    ///
+    /// This is only used to suggest similar names when a module is not found,
+    /// so a file system that cannot list directories may keep the default.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Cannot list {}", path.to_string_lossy()),
+        ))
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.metadata(path).map(|m| m.is_file).unwrap_or(false)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.metadata(path).map(|m| m.is_dir).unwrap_or(false)
+    }
+}
+
+/// The file system of the operating system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsFileSystem;
+
+impl FileSystem for OsFileSystem {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        // Only a symbolic link needs a second call to find what it points to
+        let link_metadata = fs::symlink_metadata(path)?;
+        let is_symlink = link_metadata.file_type().is_symlink();
+        let metadata = match is_symlink {
+            true => fs::metadata(path)?,
+            false => link_metadata,
+        };
+
+        Ok(FileMetadata {
+            is_file: metadata.is_file(),
+            is_dir: metadata.is_dir(),
+            is_symlink,
+        })
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::read_link(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        path.canonicalize()
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect()
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+}
+
+macro_rules! forward_file_system {
+    ($($t:ty),*) => {
+        $(
+            impl<F: FileSystem + ?Sized> FileSystem for $t {
+                fn read_to_string(&self, path: &Path) -> io::Result<String> {
+                    (**self).read_to_string(path)
+                }
+
+                fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+                    (**self).metadata(path)
+                }
+
+                fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+                    (**self).read_link(path)
+                }
+
+                fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+                    (**self).canonicalize(path)
+                }
+
+                fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+                    (**self).read_dir(path)
+                }
+
+                fn is_file(&self, path: &Path) -> bool {
+                    (**self).is_file(path)
+                }
+
+                fn is_dir(&self, path: &Path) -> bool {
+                    (**self).is_dir(path)
+                }
+            }
+        )*
+    };
+}
+
+forward_file_system!(&F, Box<F>, Arc<F>);
@@ -1,6 +1,14 @@
 #![doc = include_str!("../README.md")]
 
+mod cache;
+mod context;
 mod es_resolver;
+mod file_system;
+mod memory_file_system;
+mod overlay_file_system;
+mod resolver;
+mod suggestions;
+mod trace;
 mod types;
 mod data;
 mod utils;
@@ -9,9 +17,25 @@ mod utils;
 mod tests;
 
 pub use es_resolver::EsResolver;
+pub use file_system::{FileMetadata, FileSystem, OsFileSystem};
+pub use memory_file_system::MemoryFileSystem;
+pub use overlay_file_system::OverlayFileSystem;
+pub use resolver::Resolver;
+pub use suggestions::Suggestion;
+pub use trace::{ResolveTrace, TraceStep};
 pub use types::{
+  AliasTarget,
+  BrowserBuiltins,
   TargetEnv,
   EsResolverError,
   EsResolveOptions,
   Extensions,
+  Externals,
+  MainFields,
+  MatchedBy,
+  NodeVersion,
+  PackageInfo,
+  ResolveKind,
+  Resolution,
+  ResolutionKind,
 };
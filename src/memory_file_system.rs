@@ -0,0 +1,323 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use path_clean::PathClean;
+use serde_json::Value;
+
+use crate::file_system::{FileMetadata, FileSystem};
+
+/// Symbolic links nested deeper than this are considered a loop, like `ELOOP`.
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// The key that marks an object in a fixture description as a symbolic link.
+/// See [`MemoryFileSystem::from_json`].
+pub static SYMLINK_KEY: &str = "$symlink";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Entry {
+    File(String),
+    Dir,
+    Symlink(PathBuf),
+}
+
+/// A [`FileSystem`] that lives entirely in memory.
+///
+/// Relative paths are taken as relative to the root `/`. Parent directories are created
+/// implicitly when a file is added. Symbolic links are never created implicitly, add them
+/// with [`MemoryFileSystem::add_symlink`].
+///
+/// ```rust
+/// use std::path::Path;
+/// use es_resolve::*;
+///
+/// let fs = MemoryFileSystem::from_files([
+///     ("/app/index.js", ""),
+///     ("/app/node_modules/lib/index.js", ""),
+/// ]);
+///
+/// let resolver = Resolver::with_file_system(
+///     TargetEnv::Node,
+///     EsResolveOptions::default_for(TargetEnv::Node),
+///     fs,
+/// );
+///
+/// let resolution = resolver.resolve("lib", Path::new("/app/index.js")).unwrap();
+/// assert_eq!(resolution.path, Path::new("/app/node_modules/lib/index.js"));
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryFileSystem {
+    entries: RwLock<BTreeMap<PathBuf, Entry>>,
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a file system from pairs of path and file content.
+    pub fn from_files<P: AsRef<Path>, S: Into<String>>(
+        files: impl IntoIterator<Item = (P, S)>,
+    ) -> Self {
+        let fs = Self::new();
+
+        for (path, content) in files {
+            fs.add_file(path, content);
+        }
+
+        fs
+    }
+
+    /// Build a file system from a JSON description of a directory tree.
+    ///
+    /// Every key of an object is a path relative to the object's directory, and its value is
+    /// - a string, for a file with that content;
+    /// - an object, for a directory. A key ending in `.json` is a file with the object
+    ///   serialized as its content instead, which is handy for package.json;
+    /// - an object with the single key `"$symlink"`, for a symbolic link to that target.
+    ///
+    /// ```json
+    /// {
+    ///   "/app": {
+    ///     "index.js": "import 'lib'",
+    ///     "node_modules": {
+    ///       "lib": { "$symlink": "../packages/lib" }
+    ///     },
+    ///     "packages/lib": {
+    ///       "package.json": { "main": "./main.js" },
+    ///       "main.js": ""
+    ///     }
+    ///   }
+    /// }
+    /// ```
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        Self::from_value(&value).map_err(serde::de::Error::custom)
+    }
+
+    /// Like [`MemoryFileSystem::from_json`], but the tree is described in YAML.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        let value: Value = serde_yaml::from_str(yaml)?;
+        Self::from_value(&value).map_err(serde::de::Error::custom)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, String> {
+        let fs = Self::new();
+        fs.add_value(Path::new("/"), value)?;
+        Ok(fs)
+    }
+
+    fn add_value(&self, path: &Path, value: &Value) -> Result<(), String> {
+        match value {
+            Value::String(content) => self.add_file(path, content.as_str()),
+            Value::Object(map) => {
+                if let (1, Some(Value::String(target))) = (map.len(), map.get(SYMLINK_KEY)) {
+                    self.add_symlink(path, target);
+                } else if path.extension().is_some_and(|ext| ext == "json") {
+                    self.add_file(path, value.to_string());
+                } else {
+                    self.add_dir(path);
+
+                    for (name, child) in map {
+                        self.add_value(&path.join(name), child)?;
+                    }
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "{} should be described by a string or an object, got {}",
+                    path.to_string_lossy(),
+                    value
+                ))
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Add or replace a file, creating its parent directories.
+    pub fn add_file(&self, path: impl AsRef<Path>, content: impl Into<String>) {
+        let path = normalize(path.as_ref());
+
+        if let Some(parent) = path.parent() {
+            self.add_dir(parent);
+        }
+
+        self.write().insert(path, Entry::File(content.into()));
+    }
+
+    /// Add a directory and all of its parents.
+    pub fn add_dir(&self, path: impl AsRef<Path>) {
+        let path = normalize(path.as_ref());
+        let mut entries = self.write();
+
+        for dir in path.ancestors() {
+            entries.entry(dir.to_path_buf()).or_insert(Entry::Dir);
+        }
+    }
+
+    /// Add a symbolic link at `path` pointing to `target`.
+    /// A relative `target` is relative to the directory containing `path`, as it is on disk.
+    pub fn add_symlink(&self, path: impl AsRef<Path>, target: impl AsRef<Path>) {
+        let path = normalize(path.as_ref());
+
+        if let Some(parent) = path.parent() {
+            self.add_dir(parent);
+        }
+
+        self.write()
+            .insert(path, Entry::Symlink(target.as_ref().to_path_buf()));
+    }
+
+    /// Remove a file, a symbolic link or a directory with everything in it.
+    pub fn remove(&self, path: impl AsRef<Path>) {
+        let path = normalize(path.as_ref());
+
+        self.write().retain(|p, _| !p.starts_with(&path));
+    }
+
+    /// Whether `path` has been added, without following symbolic links.
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        self.read().contains_key(&normalize(path.as_ref()))
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, BTreeMap<PathBuf, Entry>> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, BTreeMap<PathBuf, Entry>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Resolve every symbolic link in `path`. The result may not exist.
+    fn real_path(&self, path: &Path) -> io::Result<PathBuf> {
+        let entries = self.read();
+        let mut path = normalize(path);
+
+        'restart: for _ in 0..=MAX_SYMLINK_HOPS {
+            let mut resolved = PathBuf::new();
+            let mut components = path.components();
+
+            while let Some(component) = components.next() {
+                resolved.push(component);
+
+                if let Some(Entry::Symlink(target)) = entries.get(&resolved) {
+                    resolved.pop();
+                    path = normalize(&resolved.join(target).join(components.as_path()));
+                    continue 'restart;
+                }
+            }
+
+            return Ok(resolved);
+        }
+
+        Err(io::Error::other(format!(
+            "Too many levels of symbolic links: {}",
+            path.to_string_lossy()
+        )))
+    }
+
+    /// The entry at `path`, where only the symbolic links in its parents are followed.
+    fn entry_no_follow(&self, path: &Path) -> io::Result<Entry> {
+        let path = normalize(path);
+        let resolved = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => self.real_path(parent)?.join(name),
+            _ => path,
+        };
+
+        self.read()
+            .get(&resolved)
+            .cloned()
+            .ok_or_else(|| not_found(&resolved))
+    }
+
+    fn entry(&self, path: &Path) -> io::Result<(PathBuf, Entry)> {
+        let resolved = self.real_path(path)?;
+        let entry = self.read().get(&resolved).cloned();
+
+        match entry {
+            Some(entry) => Ok((resolved, entry)),
+            None => Err(not_found(path)),
+        }
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        match self.entry(path)? {
+            (_, Entry::File(content)) => Ok(content),
+            _ => Err(io::Error::other(format!(
+                "{} is a directory",
+                path.to_string_lossy()
+            ))),
+        }
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        let (_, entry) = self.entry(path)?;
+
+        Ok(FileMetadata {
+            is_file: matches!(entry, Entry::File(_)),
+            is_dir: matches!(entry, Entry::Dir),
+            is_symlink: matches!(self.entry_no_follow(path), Ok(Entry::Symlink(_))),
+        })
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.entry_no_follow(path)? {
+            Entry::Symlink(target) => Ok(target),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a symbolic link", path.to_string_lossy()),
+            )),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.entry(path).map(|(resolved, _)| resolved)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let resolved = match self.entry(path)? {
+            (resolved, Entry::Dir) => resolved,
+            _ => {
+                return Err(io::Error::other(format!(
+                    "{} is not a directory",
+                    path.to_string_lossy()
+                )))
+            }
+        };
+
+        let entries = self.read();
+        let children = entries
+            .range(resolved.clone()..)
+            .map(|(p, _)| p)
+            .take_while(|p| p.starts_with(&resolved))
+            .filter(|p| p.parent() == Some(resolved.as_path()))
+            .filter_map(|p| p.file_name())
+            .map(|name| path.join(name))
+            .collect();
+
+        Ok(children)
+    }
+}
+
+/// Make `path` absolute against the root, with `.` and `..` removed.
+fn normalize(path: &Path) -> PathBuf {
+    if path.has_root() {
+        path.to_path_buf().clean()
+    } else {
+        Path::new("/").join(path).clean()
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} does not exist", path.to_string_lossy()),
+    )
+}
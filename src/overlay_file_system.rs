@@ -0,0 +1,167 @@
+use std::{
+    collections::BTreeSet,
+    io,
+    path::{Path, PathBuf},
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use path_clean::PathClean;
+
+use crate::{
+    file_system::{FileMetadata, FileSystem, OsFileSystem},
+    memory_file_system::MemoryFileSystem,
+};
+
+/// A [`FileSystem`] that layers in-memory files over another file system, the OS file system
+/// by default. This is how unsaved editor buffers can take part in resolution.
+///
+/// - [`OverlayFileSystem::add_file`] adds a file that may not exist on disk yet, or shadows
+///   the content of one that does.
+/// - [`OverlayFileSystem::shadow_file`] hides a file or directory that still exists on disk.
+/// - [`OverlayFileSystem::remove_file`] drops whatever the overlay knows about a path, so
+///   the file on disk shows through again.
+///
+/// Paths in the overlay are not canonicalized against the base file system, so they
+/// should be absolute and free of symbolic links.
+///
+/// The [`crate::Resolver`] caches what it has seen; call [`crate::Resolver::clear_cache`]
+/// after changing the overlay.
+#[derive(Debug, Default)]
+pub struct OverlayFileSystem<F: FileSystem = OsFileSystem> {
+    base: F,
+    overlay: MemoryFileSystem,
+    shadowed: RwLock<BTreeSet<PathBuf>>,
+}
+
+impl<F: FileSystem> OverlayFileSystem<F> {
+    pub fn new(base: F) -> Self {
+        Self {
+            base,
+            overlay: MemoryFileSystem::new(),
+            shadowed: RwLock::default(),
+        }
+    }
+
+    pub fn base(&self) -> &F {
+        &self.base
+    }
+
+    /// Add a file to the overlay, shadowing the file on disk if there is one.
+    pub fn add_file(&self, path: impl AsRef<Path>, content: impl Into<String>) {
+        let path = path.as_ref().to_path_buf().clean();
+
+        self.write_shadowed().remove(&path);
+        self.overlay.add_file(path, content);
+    }
+
+    /// Make a file or a directory look deleted, whether or not it exists on disk.
+    pub fn shadow_file(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref().to_path_buf().clean();
+
+        self.overlay.remove(&path);
+        self.write_shadowed().insert(path);
+    }
+
+    /// Forget both the content added and the shadowing done to `path`.
+    pub fn remove_file(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref().to_path_buf().clean();
+
+        self.overlay.remove(&path);
+        self.write_shadowed().remove(&path);
+    }
+
+    fn read_shadowed(&self) -> RwLockReadGuard<'_, BTreeSet<PathBuf>> {
+        self.shadowed.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write_shadowed(&self) -> RwLockWriteGuard<'_, BTreeSet<PathBuf>> {
+        self.shadowed.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn in_overlay(&self, path: &Path) -> bool {
+        self.overlay.metadata(path).is_ok()
+    }
+
+    /// Whether `path` or one of its parents has been shadowed.
+    fn is_shadowed(&self, path: &Path) -> bool {
+        let shadowed = self.read_shadowed();
+
+        !shadowed.is_empty()
+            && path
+                .to_path_buf()
+                .clean()
+                .ancestors()
+                .any(|p| shadowed.contains(p))
+    }
+
+    fn check_shadowed(&self, path: &Path) -> io::Result<()> {
+        if self.is_shadowed(path) {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is shadowed by the overlay", path.to_string_lossy()),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<F: FileSystem> FileSystem for OverlayFileSystem<F> {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        if self.in_overlay(path) {
+            return self.overlay.read_to_string(path);
+        }
+
+        self.check_shadowed(path)?;
+        self.base.read_to_string(path)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        if let Ok(metadata) = self.overlay.metadata(path) {
+            return Ok(metadata);
+        }
+
+        self.check_shadowed(path)?;
+        self.base.metadata(path)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.in_overlay(path) {
+            return self.overlay.read_link(path);
+        }
+
+        self.check_shadowed(path)?;
+        self.base.read_link(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.in_overlay(path) {
+            return self.overlay.canonicalize(path);
+        }
+
+        self.check_shadowed(path)?;
+        self.base.canonicalize(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        self.check_shadowed(path)?;
+
+        let mut children = BTreeSet::new();
+        let base = self.base.read_dir(path);
+        let overlay = self.overlay.read_dir(path);
+
+        if base.is_err() && overlay.is_err() {
+            return base;
+        }
+
+        for child in base.into_iter().flatten() {
+            if !self.is_shadowed(&child) {
+                children.insert(child);
+            }
+        }
+
+        children.extend(overlay.into_iter().flatten());
+
+        Ok(children.into_iter().collect())
+    }
+}
@@ -0,0 +1,1974 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    cache::ResolverCache,
+    context::ResolveContext,
+    data::*,
+    file_system::{FileSystem, OsFileSystem},
+    suggestions::{suggest, Suggestion},
+    trace::{ResolveTrace, TraceStep},
+    types::*,
+    utils::*,
+};
+use indexmap::IndexMap;
+use path_clean::PathClean;
+use tracing::debug;
+
+/// A long-lived resolver that can be shared across many [`Resolver::resolve`] calls.
+///
+/// Parsed package.json and tsconfig.json files, as well as file and directory lookups,
+/// are cached for the lifetime of the resolver. Call [`Resolver::clear_cache`] when the
+/// file system is known to have changed.
+///
+/// All I/O goes through the [`FileSystem`] `F`, which is the real file system by default.
+#[derive(Debug)]
+pub struct Resolver<F: FileSystem = OsFileSystem> {
+    pub env: TargetEnv,
+    pub options: EsResolveOptions,
+    fs: F,
+    cache: ResolverCache,
+}
+
+impl Resolver {
+    pub fn new(env: TargetEnv) -> Self {
+        Self::with_options(env.clone(), EsResolveOptions::default_for(env))
+    }
+
+    pub fn with_options(env: TargetEnv, options: EsResolveOptions) -> Self {
+        Self::with_file_system(env, options, OsFileSystem)
+    }
+}
+
+impl<F: FileSystem> Resolver<F> {
+    pub fn with_file_system(env: TargetEnv, options: EsResolveOptions, fs: F) -> Self {
+        Self {
+            env,
+            options,
+            fs,
+            cache: ResolverCache::default(),
+        }
+    }
+
+    pub fn file_system(&self) -> &F {
+        &self.fs
+    }
+
+    /// The directories looked in for a package imported from the file `from`, in order, whether
+    /// they exist or not, like [`require.resolve.paths()`](https://nodejs.org/api/modules.html#requireresolvepathsrequest).
+    /// See [`EsResolveOptions::modules`].
+    pub fn lookup_paths(&self, from: &Path) -> Vec<PathBuf> {
+        match from.parent() {
+            Some(from_dir) => self.node_modules_paths(from_dir),
+            None => Vec::new(),
+        }
+    }
+
+    /// Drop everything cached so far.
+    pub fn clear_cache(&self) {
+        self.cache.clear();
+    }
+
+    /// Resolve `specifier` as if it is imported from the file `from`.
+    ///
+    /// The query and fragment of a specifier like `./logo.svg?raw#top` are kept in the [`Resolution`]
+    /// and not part of the path resolved. If that fails, the specifier is resolved as a whole,
+    /// in case a file name contains `?` or `#`.
+    ///
+    /// Reference: <https://nodejs.org/api/modules.html#all-together>
+    #[tracing::instrument(skip(self))]
+    pub fn resolve(&self, specifier: &str, from: &Path) -> EsResolverResult<Resolution> {
+        self.resolve_explained(None, specifier, from)
+    }
+
+    /// Like [`Resolver::resolve`], but follows the rules of how `specifier` is imported.
+    ///
+    /// - The conditions `"import"`, `"require"` and `"style"` in `exports` and `imports` are enabled
+    ///   by `kind` only, while the others in [`EsResolveOptions::conditions`] still apply.
+    /// - For [`TargetEnv::Node`], an ES module import neither appends an extension nor loads the index
+    ///   file of a directory, except for the main field of a package, as Node does.
+    /// - A stylesheet import tries a bare specifier as a relative path first.
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use es_resolve::*;
+    ///
+    /// let resolver = Resolver::with_file_system(
+    ///     TargetEnv::Node,
+    ///     EsResolveOptions::default_for(TargetEnv::Node),
+    ///     MemoryFileSystem::from_files([("/app/index.js", ""), ("/app/utils.js", "")]),
+    /// );
+    /// let from = Path::new("/app/index.js");
+    ///
+    /// assert!(resolver.resolve_with_kind("./utils", from, ResolveKind::Require).is_ok());
+    /// assert!(resolver.resolve_with_kind("./utils", from, ResolveKind::Import).is_err());
+    /// assert!(resolver.resolve_with_kind("./utils.js", from, ResolveKind::Import).is_ok());
+    /// ```
+    #[tracing::instrument(skip(self))]
+    pub fn resolve_with_kind(
+        &self,
+        specifier: &str,
+        from: &Path,
+        kind: ResolveKind,
+    ) -> EsResolverResult<Resolution> {
+        self.resolve_explained(Some(kind), specifier, from)
+    }
+
+    fn resolve_explained(
+        &self,
+        kind: Option<ResolveKind>,
+        specifier: &str,
+        from: &Path,
+    ) -> EsResolverResult<Resolution> {
+        // Suggestions are made from the places visited, so trace them.
+        let mut ctx = match self.options.suggestions {
+            true => ResolveContext {
+                kind,
+                ..ResolveContext::with_trace()
+            },
+            false => ResolveContext::with_kind(kind),
+        };
+
+        match self.resolve_with_context(&mut ctx, specifier, from) {
+            Err(e) => {
+                let trace = ctx.trace.unwrap_or_default();
+
+                Err(self.explain_error(e, specifier, &trace))
+            }
+            result => result,
+        }
+    }
+
+    /// Like [`Resolver::resolve`], but also returns every step taken.
+    /// A [`EsResolverError::ModuleNotFound`] carries the steps taken before giving up.
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use es_resolve::*;
+    ///
+    /// let resolver = Resolver::with_file_system(
+    ///     TargetEnv::Node,
+    ///     EsResolveOptions::default_for(TargetEnv::Node),
+    ///     MemoryFileSystem::from_files([("/app/index.js", "")]),
+    /// );
+    ///
+    /// match resolver.resolve_with_trace("./utils", Path::new("/app/index.js")) {
+    ///     Err(EsResolverError::ModuleNotFound { trace, .. }) => {
+    ///         assert!(trace.steps.contains(&TraceStep::File {
+    ///             path: "/app/utils.js".into(),
+    ///             found: false,
+    ///         }));
+    ///         println!("here is what was tried:\n{}", trace);
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    #[tracing::instrument(skip(self))]
+    pub fn resolve_with_trace(
+        &self,
+        specifier: &str,
+        from: &Path,
+    ) -> EsResolverResult<(Resolution, ResolveTrace)> {
+        let mut ctx = ResolveContext::with_trace();
+        let result = self.resolve_with_context(&mut ctx, specifier, from);
+        let trace = ctx.trace.unwrap_or_default();
+
+        match result {
+            Ok(resolution) => Ok((resolution, trace)),
+            Err(e) => match self.explain_error(e, specifier, &trace) {
+                EsResolverError::ModuleNotFound {
+                    message,
+                    suggestions,
+                    ..
+                } => Err(EsResolverError::ModuleNotFound {
+                    message,
+                    trace,
+                    suggestions,
+                }),
+                e => Err(e),
+            },
+        }
+    }
+
+    /// Attach "did you mean" suggestions to `e`, found in the places visited in `trace`,
+    /// unless it already has some or [`EsResolveOptions::suggestions`] is off.
+    fn explain_error(
+        &self,
+        e: EsResolverError,
+        specifier: &str,
+        trace: &ResolveTrace,
+    ) -> EsResolverError {
+        if !self.options.suggestions {
+            return e;
+        }
+
+        match e {
+            EsResolverError::ModuleNotFound {
+                message,
+                trace: kept_trace,
+                suggestions,
+            } => EsResolverError::ModuleNotFound {
+                message,
+                trace: kept_trace,
+                suggestions: match suggestions.is_empty() {
+                    true => suggest(&self.fs, specifier, trace),
+                    false => suggestions,
+                },
+            },
+            EsResolverError::PackagePathNotExported {
+                package, subpath, ..
+            } => EsResolverError::PackagePathNotExported {
+                package,
+                subpath,
+                suggestions: suggest(&self.fs, specifier, trace),
+            },
+            e => e,
+        }
+    }
+
+    fn resolve_with_context(
+        &self,
+        ctx: &mut ResolveContext,
+        specifier: &str,
+        from: &Path,
+    ) -> EsResolverResult<Resolution> {
+        let (path, query, fragment) = match split_query_fragment(specifier) {
+            (_, None, None) => return self.resolve_once(ctx, specifier, from),
+            split => split,
+        };
+
+        match self.resolve_once(ctx, path, from) {
+            Ok(resolution) => Ok(Resolution {
+                query: query.map(String::from),
+                fragment: fragment.map(String::from),
+                ..resolution
+            }),
+            Err(err) => {
+                debug!("retrying {:?} with its query and fragment", specifier);
+
+                self.resolve_once(ctx, specifier, from).map_err(|_| err)
+            }
+        }
+    }
+
+    fn resolve_once(
+        &self,
+        ctx: &mut ResolveContext,
+        specifier: &str,
+        from: &Path,
+    ) -> EsResolverResult<Resolution> {
+        ctx.matched_by = None;
+
+        if let Some(resolution) = self.external_resolution(specifier, from) {
+            return Ok(resolution);
+        }
+
+        let resolution = self.resolve_impl(ctx, specifier, from, false)?;
+
+        if resolution.kind == ResolutionKind::NodeBuiltin {
+            return Ok(resolution);
+        }
+
+        let package = match resolution.kind {
+            ResolutionKind::File => self.package_info(&resolution.path),
+            _ => None,
+        };
+
+        Ok(Resolution {
+            package,
+            matched_by: ctx.matched_by.take(),
+            ..resolution
+        })
+    }
+
+    /// Resolve `specifier` as it is if it matches any of [`EsResolveOptions::externals`].
+    fn external_resolution(&self, specifier: &str, from: &Path) -> Option<Resolution> {
+        if self.options.externals.is_empty()
+            || specifier.starts_with(['.', '/', '#'])
+            || specifier.starts_with("node:")
+            || self.is_node_builtin(specifier, false)
+        {
+            return None;
+        }
+
+        let (package, _) = parse_package_name(specifier).ok()?;
+
+        let external = self
+            .options
+            .externals
+            .iter()
+            .any(|external| match external {
+                Externals::Exact(name) => specifier == name,
+                Externals::Prefix(prefix) => specifier.starts_with(prefix.as_str()),
+                Externals::Regex(regex) => regex.is_match(specifier),
+                Externals::Dependencies => from
+                    .parent()
+                    .and_then(|from_dir| self.lookup_package_scope(from_dir))
+                    .and_then(|package_json| self.load_package_json(&package_json).ok().flatten())
+                    .is_some_and(|package_json| package_json.has_dependency(package)),
+            });
+
+        if !external {
+            return None;
+        }
+
+        debug!(package = package, "matched by externals");
+
+        let package_info = match self.options.verify_externals {
+            true => {
+                let package_dir = self
+                    .node_modules_paths(from.parent()?)
+                    .into_iter()
+                    .map(|dir| dir.join(package))
+                    .find(|dir| self.is_dir(dir))?;
+
+                self.package_info(&package_dir.join(PACKAGE_JSON))
+            }
+            false => None,
+        };
+
+        Some(Resolution {
+            package: package_info,
+            ..Resolution::external(specifier, package)
+        })
+    }
+
+    /// The package `path` belongs to.
+    fn package_info(&self, path: &Path) -> Option<PackageInfo> {
+        let package_json_path = self.lookup_package_scope(path.parent()?)?;
+        let package_json = self.load_package_json(&package_json_path).ok().flatten();
+
+        Some(PackageInfo {
+            name: package_json.as_ref().and_then(|p| p.name.clone()),
+            version: package_json.as_ref().and_then(|p| p.version.clone()),
+            package_json: package_json_path,
+        })
+    }
+
+    #[tracing::instrument(skip(self, ctx))]
+    fn resolve_impl(
+        &self,
+        ctx: &mut ResolveContext,
+        target: &str,
+        from: &Path,
+        is_tsconfig: bool,
+    ) -> EsResolverResult<Resolution> {
+        debug!("resolving {:?} from {:?}", target, from);
+
+        if !is_tsconfig {
+            if let Some(r) = self.alias_resolution(ctx, target, from, /* fallback */ false)? {
+                return Ok(r);
+            }
+
+            if let Some(r) = self.module_name_mapper_resolution(ctx, target, from)? {
+                return Ok(r);
+            }
+        }
+
+        if !is_tsconfig && matches!(self.env, TargetEnv::Node) {
+            if let Some(name) = target.strip_prefix("node:") {
+                return match self.is_node_builtin(name, true) {
+                    true => Ok(Resolution::node_builtin(target)),
+                    false => Err(EsResolverError::UnknownBuiltinModule(target.to_string())),
+                };
+            } else if self.is_node_builtin(target, false) {
+                return Ok(Resolution::node_builtin(&format!("node:{}", target)));
+            }
+        }
+
+        let abs_from = self.canonicalize(from).map_err(|e| {
+            EsResolverError::IOError(
+                e,
+                format!(
+                    "Cannot resolve from file {}. Does the file exist?",
+                    from.to_string_lossy()
+                ),
+            )
+        })?;
+
+        // If X begins with "#"
+        if target.starts_with('#') {
+            let f = self.package_imports_resolve(ctx, target, &abs_from)?;
+            return self.browser_file_resolution(ctx, f);
+        }
+
+        // If X begins with './' or '/' or '../'
+        if target.starts_with('.') || target.starts_with('/') {
+            // a. LOAD_AS_FILE(Y + X)
+            let abs_to = abs_from.with_file_name(target);
+
+            if let Some(f) = self.load_as_relative(ctx, &abs_to) {
+                return self.browser_file_resolution(ctx, f);
+            }
+
+            if self.fully_specified(ctx) {
+                if let Some(specifier) = self.fully_specified_hint(target, &abs_to) {
+                    return Err(EsResolverError::ModuleNotFound {
+                        message: format!(
+                            "Cannot resolve {:?} from {}, which must be fully specified with the extension",
+                            target,
+                            from.to_string_lossy(),
+                        ),
+                        trace: ResolveTrace::default(),
+                        suggestions: vec![Suggestion::FullySpecified(specifier)],
+                    });
+                }
+            }
+        } else {
+            if !is_tsconfig && ctx.kind.is_some_and(|kind| kind.prefers_relative()) {
+                if let Some(f) = self.load_as_relative(ctx, &abs_from.with_file_name(target)) {
+                    return self.browser_file_resolution(ctx, f);
+                }
+            }
+
+            if let Some(r) = self.browser_package_resolution(ctx, target, &abs_from)? {
+                return Ok(r);
+            }
+
+            if !is_tsconfig {
+                match self.resolve_tsconfig(ctx, from) {
+                    Ok(Some(tsconfig)) => {
+                        if let (maybe_base_url, Some(paths)) = (
+                            &tsconfig.compiler_options.base_url,
+                            &tsconfig.compiler_options.paths,
+                        ) {
+                            let base_url = maybe_base_url.as_deref().unwrap_or(".");
+
+                            let (key, paths_to_try) =
+                                self.match_tsconfig_paths(target, base_url, paths);
+
+                            ctx.trace(|| TraceStep::TSConfigPaths {
+                                key: key.to_string(),
+                                paths: paths_to_try
+                                    .iter()
+                                    .map(|p| PathBuf::from(p).clean().to_string_lossy().into())
+                                    .collect(),
+                            });
+
+                            for p in paths_to_try {
+                                if let Some(f) = self.load_as_relative(ctx, &PathBuf::from(p)) {
+                                    ctx.matched_by = Some(MatchedBy::TSConfigPaths(key.into()));
+                                    return self.browser_file_resolution(ctx, f);
+                                }
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        debug!("cannot locate a tsconfig for {:?}", from);
+                    }
+                    Err(e) => {
+                        debug!("fail to resolve tsconfig = {:?}. Note that tsconfig.json cannot have trailing comma as one of our caveats.", e);
+                        return Err(e);
+                    }
+                }
+            }
+
+            debug!("trying to load {:?} as a node module", target);
+
+            if let Some(from_dir) = abs_from.parent() {
+                if let Some(f) = self.load_node_modules(ctx, from_dir, target)? {
+                    return self.browser_file_resolution(ctx, f);
+                }
+            }
+
+            if !is_tsconfig {
+                if let Some(r) =
+                    self.alias_resolution(ctx, target, from, /* fallback */ true)?
+                {
+                    return Ok(r);
+                }
+            }
+
+            if !is_tsconfig && matches!(self.env, TargetEnv::Browser) {
+                if let Some(r) = self.browser_builtin_resolution(ctx, target, from)? {
+                    return Ok(r);
+                }
+            }
+        }
+
+        Err(EsResolverError::module_not_found(format!(
+            "Cannot resolve {:?} from {}",
+            target,
+            from.to_string_lossy(),
+        )))
+    }
+
+    /// Resolve `target` replaced by the first key of [`EsResolveOptions::alias`] that matches it,
+    /// or of [`EsResolveOptions::fallback`] if `fallback`.
+    /// A key already applied to get `target` is skipped, so that `react` aliased to `react/compat`
+    /// does not loop.
+    fn alias_resolution(
+        &self,
+        ctx: &mut ResolveContext,
+        target: &str,
+        from: &Path,
+        fallback: bool,
+    ) -> EsResolverResult<Option<Resolution>> {
+        let alias = match fallback {
+            true => &self.options.fallback,
+            false => &self.options.alias,
+        };
+        let matched_by = |key: &String| match fallback {
+            true => MatchedBy::Fallback(key.clone()),
+            false => MatchedBy::Alias(key.clone()),
+        };
+
+        for (index, (key, alias_targets)) in alias.iter().enumerate() {
+            if ctx.applied_aliases(fallback).contains(&index) {
+                continue;
+            }
+
+            let rest = match key.strip_suffix('$') {
+                Some(exact) if target == exact => "",
+                Some(_) => continue,
+                None => match target.strip_prefix(key.as_str()) {
+                    Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+                    _ => continue,
+                },
+            };
+
+            debug!(key = key, fallback = fallback, "matched by alias");
+
+            let mut last_error = None;
+
+            for alias_target in alias_targets.iter() {
+                let replacement = match alias_target {
+                    AliasTarget::Specifier(replacement) => replacement,
+                    AliasTarget::Ignore => {
+                        ctx.matched_by = Some(matched_by(key));
+                        return Ok(Some(Resolution::ignored(PathBuf::from(target))));
+                    }
+                };
+                let mut aliased = format!("{}{}", replacement, rest);
+                if aliased.starts_with('.') {
+                    aliased = self
+                        .root_dir(from)
+                        .join(&aliased)
+                        .clean()
+                        .to_string_lossy()
+                        .into();
+                }
+
+                ctx.trace(|| match fallback {
+                    true => TraceStep::Fallback {
+                        key: key.clone(),
+                        target: aliased.clone(),
+                    },
+                    false => TraceStep::Alias {
+                        key: key.clone(),
+                        target: aliased.clone(),
+                    },
+                });
+
+                ctx.applied_aliases(fallback).push(index);
+                let result = self.resolve_impl(ctx, &aliased, from, false);
+                ctx.applied_aliases(fallback).pop();
+
+                match result {
+                    Ok(resolution) => {
+                        if resolution.kind != ResolutionKind::NodeBuiltin {
+                            ctx.matched_by = Some(matched_by(key));
+                        }
+
+                        return Ok(Some(resolution));
+                    }
+                    Err(e) => last_error = Some(e),
+                }
+            }
+
+            return match last_error {
+                Some(e) => Err(e),
+                None => Ok(None),
+            };
+        }
+
+        Ok(None)
+    }
+
+    /// Resolve `target` by [`EsResolveOptions::browser_builtins`] if it is a Node built-in module.
+    fn browser_builtin_resolution(
+        &self,
+        ctx: &mut ResolveContext,
+        target: &str,
+        from: &Path,
+    ) -> EsResolverResult<Option<Resolution>> {
+        if ctx.polyfilled {
+            return Ok(None);
+        }
+
+        let (name, prefixed) = match target.strip_prefix("node:") {
+            Some(name) => (name, true),
+            None => (target, false),
+        };
+
+        if !self.is_node_builtin(name, prefixed) {
+            return match prefixed {
+                true => Err(EsResolverError::UnknownBuiltinModule(target.to_string())),
+                false => Ok(None),
+            };
+        }
+
+        let not_found = |polyfill: Option<String>| EsResolverError::NodeBuiltinInBrowser {
+            name: name.to_string(),
+            from: from.to_path_buf(),
+            polyfill,
+        };
+
+        let polyfills = match &self.options.browser_builtins {
+            BrowserBuiltins::Error => {
+                let polyfill = NODE_POLYFILLS
+                    .iter()
+                    .find(|(module, _)| *module == name)
+                    .and_then(|(_, polyfill)| polyfill.map(String::from));
+
+                return Err(not_found(polyfill));
+            }
+            BrowserBuiltins::External => {
+                return Ok(Some(Resolution::node_builtin(&format!("node:{}", name))));
+            }
+            BrowserBuiltins::Empty => return Ok(Some(Resolution::ignored(PathBuf::from(target)))),
+            BrowserBuiltins::Polyfill(polyfills) => polyfills,
+        };
+
+        let polyfill = match polyfills.iter().find(|(module, _)| module == name) {
+            Some((_, AliasTarget::Specifier(polyfill))) => polyfill,
+            Some((_, AliasTarget::Ignore)) => {
+                ctx.matched_by = Some(MatchedBy::Polyfill(name.to_string()));
+                return Ok(Some(Resolution::ignored(PathBuf::from(target))));
+            }
+            None => return Err(not_found(None)),
+        };
+
+        ctx.trace(|| TraceStep::Polyfill {
+            name: name.to_string(),
+            target: polyfill.clone(),
+        });
+
+        ctx.polyfilled = true;
+        let result = self.resolve_impl(ctx, polyfill, from, false);
+        ctx.polyfilled = false;
+
+        match result {
+            Ok(resolution) => {
+                ctx.matched_by = Some(MatchedBy::Polyfill(name.to_string()));
+                Ok(Some(resolution))
+            }
+            Err(EsResolverError::ModuleNotFound { .. }) => Err(not_found(Some(polyfill.clone()))),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Resolve `target` replaced by the first pattern of [`EsResolveOptions::module_name_mapper`]
+    /// that matches it. Like Jest, a target that fails to resolve does not fall through to the
+    /// patterns after it, nor to `target` itself.
+    fn module_name_mapper_resolution(
+        &self,
+        ctx: &mut ResolveContext,
+        target: &str,
+        from: &Path,
+    ) -> EsResolverResult<Option<Resolution>> {
+        if ctx.mapped {
+            return Ok(None);
+        }
+
+        for (pattern, mapped_targets) in self.options.module_name_mapper.iter() {
+            let captures = match pattern.captures(target) {
+                Some(captures) => captures,
+                None => continue,
+            };
+
+            debug!(pattern = pattern.as_str(), "matched by module name mapper");
+
+            let root_dir = self.root_dir(from);
+            let mut last_error = None;
+
+            for mapped_target in mapped_targets.iter() {
+                let mapped = expand_captures(mapped_target, &captures)
+                    .replace("<rootDir>", &root_dir.to_string_lossy());
+
+                ctx.trace(|| TraceStep::ModuleNameMapper {
+                    pattern: pattern.to_string(),
+                    target: mapped.clone(),
+                });
+
+                ctx.mapped = true;
+                let result = self.resolve_impl(ctx, &mapped, from, false);
+                ctx.mapped = false;
+
+                match result {
+                    Ok(resolution) => {
+                        if resolution.kind != ResolutionKind::NodeBuiltin {
+                            ctx.matched_by = Some(MatchedBy::ModuleNameMapper(pattern.to_string()));
+                        }
+
+                        return Ok(Some(resolution));
+                    }
+                    Err(e) => last_error = Some(e),
+                }
+            }
+
+            return match last_error {
+                Some(e) => Err(e),
+                None => Ok(None),
+            };
+        }
+
+        Ok(None)
+    }
+
+    /// The directory relative alias targets and `<rootDir>` are resolved from.
+    /// See [`EsResolveOptions::root_dir`].
+    fn root_dir(&self, from: &Path) -> PathBuf {
+        match &self.options.root_dir {
+            Some(root_dir) => root_dir.clone(),
+            None => from
+                .parent()
+                .and_then(|from_dir| self.lookup_package_scope(from_dir))
+                .and_then(|package_json| package_json.parent().map(Path::to_path_buf))
+                .or_else(|| from.parent().map(Path::to_path_buf))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Whether `name` is a built-in module of [`EsResolveOptions::node_version`]. One that is
+    /// only available with the `node:` prefix, like `test`, has to be `prefixed`.
+    fn is_node_builtin(&self, name: &str, prefixed: bool) -> bool {
+        let find = |modules: &[(&str, NodeVersion)]| {
+            modules
+                .binary_search_by_key(&name, |(module, _)| *module)
+                .ok()
+                .map(|i| modules[i].1)
+        };
+        let since = find(NODE_CORE_MODULES).or_else(|| match prefixed {
+            true => find(NODE_PREFIX_ONLY_MODULES),
+            false => None,
+        });
+
+        since.is_some_and(|since| {
+            self.options
+                .node_version
+                .is_none_or(|version| since <= version)
+        })
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.cache.is_file(path, || self.fs.is_file(path))
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.cache.is_dir(path, || self.fs.is_dir(path))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.cache.canonicalize(path, || self.fs.canonicalize(path))
+    }
+
+    /// Whether a path must be written in full, i.e. with its extension and not a directory.
+    /// See [`EsResolveOptions::fully_specified`]. The `extends` of a tsconfig.json is exempt, as
+    /// TypeScript adds the `.json` itself.
+    fn fully_specified(&self, ctx: &ResolveContext) -> bool {
+        !ctx.tsconfig
+            && (self.options.fully_specified
+                || matches!(self.env, TargetEnv::Node)
+                    && ctx.kind.is_some_and(|kind| kind.is_esm()))
+    }
+
+    /// The specifier that `target` would be if it is fully specified, e.g. `./foo.js` for `./foo`,
+    /// if the file it is short for exists.
+    fn fully_specified_hint(&self, target: &str, abs_to: &Path) -> Option<String> {
+        let mut ctx = ResolveContext::default();
+        let found = self
+            .load_as_file(&mut ctx, abs_to, &self.options.extensions)
+            .or_else(|| self.load_as_directory(&mut ctx, abs_to))?;
+
+        let found = found.clean();
+        let abs_to = abs_to.to_path_buf().clean();
+        let omitted = found.to_str()?.strip_prefix(abs_to.to_str()?)?;
+
+        Some(format!("{}{}", target.trim_end_matches('/'), omitted))
+    }
+
+    /// Whether `condition` in `exports` or `imports` is enabled.
+    fn condition_enabled(&self, ctx: &ResolveContext, condition: &str) -> bool {
+        match ctx.kind {
+            Some(kind) if ResolveKind::CONDITIONS.contains(&condition) => {
+                kind.conditions().contains(&condition)
+            }
+            _ => condition == "default" || self.options.conditions.iter().any(|c| c == condition),
+        }
+    }
+
+    fn load_as_relative(&self, ctx: &mut ResolveContext, abs_to: &Path) -> Option<PathBuf> {
+        if self.fully_specified(ctx) {
+            return self.load_as_file(ctx, abs_to, &[]);
+        }
+
+        self.load_as_file(ctx, abs_to, &self.options.extensions)
+            .or_else(|| self.load_as_directory(ctx, abs_to))
+    }
+
+    /// Here we follow esbuild in resolving path:
+    /// Node's standard:
+    /// LOAD_AS_FILE(X)
+    /// 1. If X is a file, load X as its file extension format. STOP
+    /// 2. If X.js is a file, load X.js as JavaScript text. STOP
+    /// 3. If X.json is a file, parse X.json to a JavaScript Object. STOP
+    /// 4. If X.node is a file, load X.node as binary addon. STOP
+    ///
+    /// Esbuild's way: <https://github.com/evanw/esbuild/blob/81fa2ca2e71a0518fe1e411276593ef6ea21a380/internal/resolver/resolver.go#L1388>
+    ///
+    #[tracing::instrument(skip(self, ctx))]
+    fn load_as_file(
+        &self,
+        ctx: &mut ResolveContext,
+        abs_to: &Path,
+        extensions: &[Extensions],
+    ) -> Option<PathBuf> {
+        let is_file = self.is_file(abs_to);
+
+        ctx.trace(|| TraceStep::File {
+            path: abs_to.to_path_buf().clean(),
+            found: is_file,
+        });
+
+        if is_file {
+            debug!("matched by exact path {}", abs_to.to_string_lossy());
+
+            ctx.matched_by = Some(MatchedBy::ExactPath);
+            return Some(abs_to.to_path_buf());
+        } else {
+            for extension in extensions.iter() {
+                if let Some(p) = self.try_extension(ctx, abs_to, extension) {
+                    debug!(
+                        path = format!("{}", p.to_string_lossy()),
+                        extension = format!("{:?}", extension),
+                        "matched by appending extension"
+                    );
+
+                    ctx.matched_by = Some(MatchedBy::Extension(extension.clone()));
+                    return Some(p);
+                }
+            }
+
+            // Node does not rewrite the extension of a fully specified path, but TypeScript does.
+            let extension_alias: &[(Extensions, Vec<Extensions>)] =
+                match self.fully_specified(ctx) && !self.options.fully_specified_rewrites {
+                    true => &[],
+                    false => &self.options.extension_alias,
+                };
+
+            for (alias, try_extensions) in extension_alias.iter() {
+                let Some(without_alias) = strip_extension(abs_to, alias.to_str()) else {
+                    continue;
+                };
+
+                for extension in try_extensions.iter() {
+                    if let Some(p) = self.try_extension(ctx, &without_alias, extension) {
+                        debug!(
+                            path = format!("{}", p.to_string_lossy()),
+                            extension = format!("{:?}", extension),
+                            "matched by extension alias"
+                        );
+
+                        ctx.matched_by = Some(MatchedBy::Extension(extension.clone()));
+                        return Some(p);
+                    }
+                }
+            }
+        }
+
+        debug!(
+            "cannot match {} with extensions {:?}",
+            abs_to.to_string_lossy(),
+            extensions
+        );
+
+        None
+    }
+
+    /// Node's standard:
+    /// LOAD_AS_DIRECTORY(X)
+    /// 1. If X/package.json is a file,
+    ///    a. Parse X/package.json, and look for "main" field.
+    ///    b. If "main" is a falsy value, GOTO 2.
+    ///    c. let M = X + (json main field)
+    ///    d. LOAD_AS_FILE(M)
+    ///    e. LOAD_INDEX(M)
+    ///    f. LOAD_INDEX(X) DEPRECATED
+    ///    g. THROW "not found"
+    /// 2. LOAD_INDEX(X)
+    ///
+    /// Esbuild: <https://github.com/evanw/esbuild/blob/81fa2ca2e71a0518fe1e411276593ef6ea21a380/internal/resolver/resolver.go#L1568>
+    ///
+    #[tracing::instrument(skip(self, ctx))]
+    fn load_as_directory(&self, ctx: &mut ResolveContext, abs_to: &Path) -> Option<PathBuf> {
+        let package_json_path = abs_to.join(PACKAGE_JSON);
+
+        // Node ignores invalid package.json (can't parse, fail to load, etc...)
+        if let Ok(Some(package_json)) = self.load_package_json(&package_json_path) {
+            // LOAD_AS_FILE(M)
+            // LOAD_INDEX(M)
+
+            for main_field in self.options.main_fields.iter() {
+                if let Some(path) = package_json.get_main_field(main_field) {
+                    ctx.trace(|| TraceStep::MainField {
+                        field: main_field.clone(),
+                        path: path.clone(),
+                    });
+
+                    let target = abs_to.join(path);
+
+                    if let c @ Some(_) = self.load_as_file(ctx, &target, &self.options.extensions) {
+                        ctx.matched_by = Some(MatchedBy::MainField(main_field.clone()));
+                        return c;
+                    }
+                }
+            }
+        }
+
+        self.load_index(ctx, abs_to)
+    }
+
+    /// Node's version:
+    /// LOAD_INDEX(X)
+    /// 1. If X/index.js is a file, load X/index.js as JavaScript text. STOP
+    /// 2. If X/index.json is a file, parse X/index.json to a JavaScript object. STOP
+    /// 3. If X/index.node is a file, load X/index.node as binary addon. STOP
+    ///
+    /// We do it as if we are trying on './directory/index', or each of [`EsResolveOptions::main_files`].
+    fn load_index(&self, ctx: &mut ResolveContext, abs_to: &Path) -> Option<PathBuf> {
+        for main_file in self.options.main_files.iter() {
+            let with_index = abs_to.join(main_file);
+
+            if let c @ Some(_) = self.load_as_file(ctx, &with_index, &self.options.extensions) {
+                ctx.matched_by = Some(MatchedBy::IndexFile);
+                return c;
+            }
+        }
+
+        None
+    }
+
+    /// Returns `Ok(None)` if there is no package.json at `p`.
+    fn load_package_json(&self, p: &Path) -> EsResolverResult<Option<Arc<PackageJSON>>> {
+        if !self.is_file(p) {
+            return Ok(None);
+        }
+
+        self.cache.package_json(p, || {
+            let content = self.fs.read_to_string(p).map_err(|e| {
+                EsResolverError::IOError(
+                    e,
+                    format!("Can't read package.json at {}", p.to_string_lossy()),
+                )
+            })?;
+
+            serde_json::from_str(&content)
+                .map(Some)
+                .map_err(|e| EsResolverError::InvalidPackageJSON(e, p.to_path_buf()))
+        })
+    }
+
+    /// Node's standard:
+    /// NODE_MODULES_PATHS(START)
+    /// 1. let PARTS = path split(START)
+    /// 2. let I = count of PARTS - 1
+    /// 3. let DIRS = []
+    /// 4. while I >= 0,
+    ///    a. if PARTS[I] = "node_modules" CONTINUE
+    ///    b. DIR = path join(PARTS[0 .. I] + "node_modules")
+    ///    c. DIRS = DIR + DIRS
+    ///    d. let I = I - 1
+    /// 5. return DIRS + GLOBAL_FOLDERS
+    ///
+    /// Each name in [`EsResolveOptions::modules`] takes the place of "node_modules",
+    /// while an absolute directory in it is only looked in after them.
+    fn node_modules_paths(&self, from_dir: &Path) -> Vec<PathBuf> {
+        let (absolute_dirs, names): (Vec<&String>, Vec<&String>) = self
+            .options
+            .modules
+            .iter()
+            .partition(|module| Path::new(module).is_absolute());
+
+        let mut dirs = Vec::new();
+
+        for dir in from_dir.ancestors() {
+            for name in names.iter() {
+                if dir.ends_with(name) {
+                    continue;
+                }
+
+                dirs.push(dir.join(name));
+            }
+        }
+
+        dirs.extend(absolute_dirs.into_iter().map(PathBuf::from));
+
+        dirs.extend(self.options.global_folders.iter().cloned());
+
+        dirs
+    }
+
+    /// Node's standard
+    /// LOAD_NODE_MODULES(X, START)
+    /// 1. let DIRS = NODE_MODULES_PATHS(START)
+    /// 2. for each DIR in DIRS:
+    ///    a. LOAD_PACKAGE_EXPORTS(X, DIR)
+    ///    b. LOAD_AS_FILE(DIR/X)
+    ///    c. LOAD_AS_DIRECTORY(DIR/X)
+    #[tracing::instrument(skip(self, ctx))]
+    fn load_node_modules(
+        &self,
+        ctx: &mut ResolveContext,
+        from_dir: &Path,
+        name: &str,
+    ) -> EsResolverResult<Option<PathBuf>> {
+        for node_modules_dir in self.node_modules_paths(from_dir) {
+            if !self.is_dir(&node_modules_dir) {
+                continue;
+            }
+
+            debug!("visiting {:?}", node_modules_dir);
+            ctx.trace(|| TraceStep::NodeModules(node_modules_dir.clone()));
+
+            match self.load_package_exports(ctx, &node_modules_dir, name) {
+                c @ Ok(Some(_)) => return c,
+                Ok(None) => {
+                    debug!("cannot load exports for package {}", name);
+                }
+                c @ (Err(EsResolverError::InvalidModuleSpecifier(_))
+                | Err(EsResolverError::IOError(_, _))) => {
+                    debug!(err = format!("{:?}", c), "load_package_exports error");
+                }
+                c => {
+                    debug!(err = format!("{:?}", c), "load_package_exports fatal error");
+                    return c;
+                }
+            }
+
+            debug!(
+                "fail to resolve by package exports at {:?}",
+                node_modules_dir
+            );
+
+            let module_base = node_modules_dir.join(name);
+
+            // An ES module still loads the main field of a package, but only a file within it.
+            let (extensions, load_directory): (&[Extensions], bool) = if self.fully_specified(ctx) {
+                (
+                    &[],
+                    parse_package_name(name).is_ok_and(|(_, subpath)| subpath.is_empty()),
+                )
+            } else {
+                (&self.options.extensions, true)
+            };
+
+            if let c @ Some(_) = self.load_as_file(ctx, &module_base, extensions) {
+                return Ok(c);
+            }
+
+            if load_directory {
+                if let c @ Some(_) = self.load_as_directory(ctx, &module_base) {
+                    return Ok(c);
+                }
+            }
+
+            debug!("fail to resolve from {:?}", node_modules_dir);
+        }
+
+        Ok(None)
+    }
+
+    fn is_conditional_exports_main_sugar(
+        &self,
+        exports: &Exports,
+        package_json_path: &Path,
+    ) -> EsResolverResult<bool> {
+        match exports {
+            Exports::String(_) | Exports::Array(_) => Ok(true),
+            Exports::Object(map) => {
+                let is_conditional_sugar = map.iter().all(|(s, _)| !s.starts_with('.'));
+                let any_conditional = map.iter().any(|(s, _)| !s.starts_with('.'));
+
+                if is_conditional_sugar == any_conditional {
+                    Ok(is_conditional_sugar)
+                } else {
+                    Err(EsResolverError::InvalidExports(
+                        format!(
+                            "The `pkg.exports` at {} here is invalid. Some keys start with '.' but some do not.",
+                            package_json_path.to_string_lossy(),
+                        )
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Reference:
+    /// Node's Standard:
+    ///     LOAD_PACKAGE_EXPORTS <https://nodejs.org/api/modules.html#all-together>
+    ///     PACKAGE_IMPORTS_RESOLVE <https://nodejs.org/api/esm.html#resolver-algorithm-specification>
+    /// Node's Source: resolve.js <https://github.com/nodejs/node/blob/main/lib/internal/modules/esm/resolve.js>
+    #[tracing::instrument(skip(self, ctx))]
+    fn load_package_exports(
+        &self,
+        ctx: &mut ResolveContext,
+        node_modules_dir: &Path,
+        name: &str,
+    ) -> EsResolverResult<Option<PathBuf>> {
+        let (package_name, _package_subpath) = parse_package_name(name)?;
+
+        let package_subpath = format!(".{}", _package_subpath);
+        // '.' when _subpath is empty, './subpath' when name is like `pkg/subpath`.
+
+        debug!(
+            package_name = format!("{:?}", package_name),
+            package_subpath = format!("{:?}", package_subpath),
+            "matching package exports"
+        );
+
+        let package_json_path = node_modules_dir.join(package_name).join(PACKAGE_JSON);
+
+        let package_json = match self.load_package_json(&package_json_path)? {
+            Some(package_json) => package_json,
+            None => {
+                debug!(
+                    package_json_path = format!("{:?}", package_json_path),
+                    "package.json doesn't exist. stop matching package exports. "
+                );
+                return Ok(None);
+            }
+        };
+
+        debug!(
+            package_json_path = format!("{:?}", package_json_path),
+            "read package.json"
+        );
+
+        let exports = match package_json.exports {
+            None => {
+                debug!(
+                    package_json_path = format!("{:?}", package_json_path),
+                    "package.json doesn't contain an `exports` field. stop matching package exports. "
+                );
+                return Ok(None);
+            }
+            Some(ref exports) => exports,
+        };
+
+        ctx.trace(|| TraceStep::MatchExports {
+            package_json: package_json_path.clone(),
+            subpath: package_subpath.clone(),
+        });
+
+        let resolved = if self.is_conditional_exports_main_sugar(exports, &package_json_path)? {
+            if package_subpath == "." {
+                debug!(
+                    package_json_path = format!("{:?}", package_json_path),
+                    "package.exports is 'conditional exports main sugar' and we match it"
+                );
+
+                let resolved = self.resolve_package_target(
+                    ctx,
+                    &package_json_path,
+                    exports,
+                    "",
+                    &package_subpath,
+                    false,
+                    false,
+                    false,
+                )?;
+
+                matched_by_key(ctx, resolved, ".", false)
+            } else {
+                None
+            }
+        } else if let Exports::Object(ref o) = exports {
+            self.package_imports_exports_resolve(
+                ctx,
+                &package_subpath,
+                o,
+                &package_json_path,
+                false,
+            )?
+        } else {
+            None
+        };
+
+        match resolved {
+            Some(p) => Ok(Some(p)),
+            None if self.options.strict_exports => Err(EsResolverError::PackagePathNotExported {
+                package: package_name.to_string(),
+                subpath: package_subpath,
+                suggestions: Vec::new(),
+            }),
+            None => {
+                debug!(
+                    package_name = format!("{:?}", package_name),
+                    package_subpath = format!("{:?}", package_subpath),
+                    "subpath is not exported. fall back to loading it as a file or directory"
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Node's standard:
+    /// PACKAGE_IMPORTS_RESOLVE(specifier, parentURL, conditions)
+    /// 1. If specifier is exactly equal to "#" or starts with "#/", then
+    ///    a. Throw an Invalid Module Specifier error.
+    /// 2. Let packageURL be the result of LOOKUP_PACKAGE_SCOPE(parentURL).
+    /// 3. If packageURL is not null, then
+    ///    a. Let pjson be the result of READ_PACKAGE_JSON(packageURL).
+    ///    b. If pjson.imports is a non-null Object, then
+    ///       1. Let resolved be the result of PACKAGE_IMPORTS_EXPORTS_RESOLVE(specifier, pjson.imports, packageURL, true, conditions).
+    ///       2. If resolved is not null or undefined, return resolved.
+    /// 4. Throw a Package Import Not Defined error.
+    #[tracing::instrument(skip(self, ctx))]
+    fn package_imports_resolve(
+        &self,
+        ctx: &mut ResolveContext,
+        specifier: &str,
+        abs_from: &Path,
+    ) -> EsResolverResult<PathBuf> {
+        if specifier == "#" || specifier.starts_with("#/") {
+            return Err(EsResolverError::InvalidModuleSpecifier(format!(
+                "{} is not a valid internal imports specifier name.",
+                specifier
+            )));
+        }
+
+        let maybe_package_json_path = abs_from
+            .parent()
+            .and_then(|from_dir| self.lookup_package_scope(from_dir));
+
+        if let Some(ref package_json_path) = maybe_package_json_path {
+            if let Some(package_json) = self.load_package_json(package_json_path)? {
+                if let Some(ref imports) = package_json.imports {
+                    ctx.trace(|| TraceStep::MatchImports {
+                        package_json: package_json_path.clone(),
+                        specifier: specifier.to_string(),
+                    });
+
+                    if let Some(p) = self.package_imports_exports_resolve(
+                        ctx,
+                        specifier,
+                        imports,
+                        package_json_path,
+                        true,
+                    )? {
+                        return Ok(p);
+                    }
+                }
+            }
+        }
+
+        Err(EsResolverError::PackageImportNotDefined(format!(
+            "Package import specifier {:?} is not defined in {} imported from {}",
+            specifier,
+            maybe_package_json_path
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_else(|| String::from("any package.json")),
+            abs_from.to_string_lossy(),
+        )))
+    }
+
+    /// Node's standard:
+    /// LOOKUP_PACKAGE_SCOPE(url)
+    /// 1. Let scopeURL be url.
+    /// 2. While scopeURL is not the file system root,
+    ///    a. Set scopeURL to the parent URL of scopeURL.
+    ///    b. If scopeURL ends in a "node_modules" path segment, return null.
+    ///    c. Let pjsonURL be the resolution of "package.json" within scopeURL.
+    ///    d. if the file at pjsonURL exists, then
+    ///       1. Return scopeURL.
+    /// 3. Return null.
+    ///
+    /// Returns the path to the package.json instead of its directory.
+    fn lookup_package_scope(&self, from_dir: &Path) -> Option<PathBuf> {
+        for dir in from_dir.ancestors() {
+            if dir.file_name().is_some_and(|name| name == NODE_MODULES) {
+                return None;
+            }
+
+            let package_json_path = dir.join(PACKAGE_JSON);
+
+            if self.is_file(&package_json_path) {
+                return Some(package_json_path);
+            }
+        }
+
+        None
+    }
+
+    /// The package.json in the package scope of `path` and its `browser` field in the object form,
+    /// when [`EsResolveOptions::browser_field`] is on.
+    ///
+    /// Like Node ignoring an invalid package.json in `LOAD_AS_DIRECTORY`, a package.json that
+    /// fails to load here is taken as one without a `browser` field.
+    fn browser_field_scope(&self, path: &Path) -> Option<(PathBuf, Arc<PackageJSON>)> {
+        if !self.options.browser_field {
+            return None;
+        }
+
+        let package_json_path = self.lookup_package_scope(path.parent()?)?;
+
+        match self.load_package_json(&package_json_path) {
+            Ok(Some(package_json))
+                if matches!(package_json.browser, Some(BrowserField::Object(_))) =>
+            {
+                Some((package_json_path, package_json))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                debug!(
+                    err = format!("{:?}", e),
+                    "ignore the browser field of an invalid package.json"
+                );
+                None
+            }
+        }
+    }
+
+    /// Remap the resolved file `path` with the `browser` field of its package.
+    ///
+    /// A key starting with `.` is a file relative to the package.json. It matches `path` with or
+    /// without the extension, or the directory `path` is the index of, e.g. `"./lib/node"` matches
+    /// `lib/node.js` while `"./lib"` matches `lib/index.js`.
+    ///
+    /// See <https://github.com/defunctzombie/package-browser-field-spec#replace-specific-files---advanced>.
+    #[tracing::instrument(skip(self, ctx))]
+    fn browser_file_resolution(
+        &self,
+        ctx: &mut ResolveContext,
+        path: PathBuf,
+    ) -> EsResolverResult<Resolution> {
+        let (package_json_path, package_json) = match self.browser_field_scope(&path) {
+            Some(scope) => scope,
+            None => return Ok(Resolution::file(path)),
+        };
+        let package_dir = package_json_path.parent().unwrap_or(Path::new("/"));
+        let path = path.clean();
+        let without_extension = path.with_extension("");
+        let index_of = path
+            .file_stem()
+            .filter(|stem| self.options.main_files.iter().any(|f| *stem == f.as_str()))
+            .and_then(|_| path.parent());
+
+        if let Some(BrowserField::Object(ref map)) = package_json.browser {
+            for (key, target) in map.iter().filter(|(key, _)| key.starts_with('.')) {
+                let key_path = package_dir.join(key).clean();
+
+                if key_path == path
+                    || key_path == without_extension
+                    || Some(key_path.as_path()) == index_of
+                {
+                    debug!(
+                        key = key,
+                        target = format!("{:?}", target),
+                        "matched by the browser field"
+                    );
+
+                    return self.browser_field_target(
+                        ctx,
+                        key,
+                        target,
+                        &path,
+                        package_dir,
+                        &package_json_path,
+                    );
+                }
+            }
+        }
+
+        Ok(Resolution::file(path))
+    }
+
+    /// Remap a package `name` imported from `abs_from` with the `browser` field of the importer's package.
+    ///
+    /// See <https://github.com/defunctzombie/package-browser-field-spec#replace-specific-files---advanced>.
+    #[tracing::instrument(skip(self, ctx))]
+    fn browser_package_resolution(
+        &self,
+        ctx: &mut ResolveContext,
+        name: &str,
+        abs_from: &Path,
+    ) -> EsResolverResult<Option<Resolution>> {
+        let (package_json_path, package_json) = match self.browser_field_scope(abs_from) {
+            Some(scope) => scope,
+            None => return Ok(None),
+        };
+        let package_dir = package_json_path.parent().unwrap_or(Path::new("/"));
+
+        match package_json.browser {
+            Some(BrowserField::Object(ref map)) => match map.get(name) {
+                Some(target) => {
+                    debug!(
+                        target = format!("{:?}", target),
+                        "matched by the browser field"
+                    );
+
+                    self.browser_field_target(
+                        ctx,
+                        name,
+                        target,
+                        Path::new(name),
+                        package_dir,
+                        &package_json_path,
+                    )
+                    .map(Some)
+                }
+                None => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Load the replacement `target` of `key` in the `browser` field. `false` ignores `ignored`.
+    fn browser_field_target(
+        &self,
+        ctx: &mut ResolveContext,
+        key: &str,
+        target: &BrowserFieldTarget,
+        ignored: &Path,
+        package_dir: &Path,
+        package_json_path: &Path,
+    ) -> EsResolverResult<Resolution> {
+        ctx.trace(|| TraceStep::BrowserField {
+            package_json: package_json_path.to_path_buf(),
+            key: key.to_string(),
+        });
+
+        let replacement = match target {
+            BrowserFieldTarget::Bool(false) => {
+                ctx.matched_by = Some(MatchedBy::BrowserField(key.to_string()));
+                return Ok(Resolution::ignored(ignored.to_path_buf()));
+            }
+            BrowserFieldTarget::Bool(true) => {
+                return Err(EsResolverError::InvalidPackageTarget {
+                    package_json: package_json_path.to_path_buf(),
+                    key: key.to_string(),
+                    target: String::from("true"),
+                    reason: String::from("browser field targets must be a string or false"),
+                })
+            }
+            BrowserFieldTarget::String(replacement) => replacement,
+        };
+
+        let loaded = if replacement.starts_with('.') {
+            self.load_as_relative(ctx, &package_dir.join(replacement))
+        } else {
+            self.load_node_modules(ctx, package_dir, replacement)?
+        };
+
+        match loaded {
+            Some(f) => {
+                ctx.matched_by = Some(MatchedBy::BrowserField(key.to_string()));
+                Ok(Resolution::file(f))
+            }
+            None => Err(EsResolverError::module_not_found(format!(
+                "Cannot resolve {:?}, which replaces {:?} by the browser field in {}",
+                replacement,
+                key,
+                package_json_path.to_string_lossy(),
+            ))),
+        }
+    }
+
+    /// Node's standard:
+    /// PACKAGE_IMPORTS_EXPORTS_RESOLVE(matchKey, matchObj, packageURL, isImports, conditions)
+    /// 1. If matchKey is a key of matchObj and does not contain "*", then
+    ///    a. Let target be the value of matchObj[matchKey].
+    ///    b. Return the result of PACKAGE_TARGET_RESOLVE(packageURL, target, null, isImports, conditions).
+    /// 2. Let expansionKeys be the list of keys of matchObj containing only a single "*", sorted by the sorting function PATTERN_KEY_COMPARE which orders in descending order of specificity.
+    /// 3. For each key expansionKey in expansionKeys, do
+    ///    ...
+    /// 4. Return null.
+    #[tracing::instrument(skip(self, ctx, match_obj))]
+    fn package_imports_exports_resolve(
+        &self,
+        ctx: &mut ResolveContext,
+        match_key: &str,
+        match_obj: &IndexMap<String, Option<Exports>>,
+        package_json_path: &Path,
+        is_imports: bool,
+    ) -> EsResolverResult<Option<PathBuf>> {
+        if !match_key.contains('*') {
+            if let Some(maybe_target) = match_obj.get(match_key) {
+                debug!(
+                    match_key = format!("{:?}", match_key),
+                    "get full non-pattern match"
+                );
+                ctx.trace(|| TraceStep::MatchedKey(match_key.to_string()));
+
+                // An explicit `null` target excludes the subpath.
+                let Some(target) = maybe_target else {
+                    return Ok(None);
+                };
+
+                let resolved = self.resolve_package_target(
+                    ctx,
+                    package_json_path,
+                    target,
+                    "",
+                    match_key,
+                    false,
+                    is_imports,
+                    false,
+                )?;
+
+                return Ok(matched_by_key(ctx, resolved, match_key, is_imports));
+            }
+        }
+
+        let mut best_match = "";
+
+        for key in match_obj.keys() {
+            if match_exports_pattern(key, match_key) && pattern_key_compare(best_match, key) == 1 {
+                best_match = key;
+            }
+        }
+
+        if !best_match.is_empty() {
+            ctx.trace(|| TraceStep::MatchedKey(best_match.to_string()));
+        }
+
+        let Some(Some(target)) = match_obj.get(best_match) else {
+            return Ok(None);
+        };
+
+        let subpath = extract_exports_pattern(best_match, match_key);
+
+        let resolved = self.resolve_package_target(
+            ctx,
+            package_json_path,
+            target,
+            subpath,
+            match_key,
+            true,
+            is_imports,
+            false,
+        )?;
+
+        Ok(matched_by_key(ctx, resolved, best_match, is_imports))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip(self, ctx))]
+    fn resolve_package_target(
+        &self,
+        ctx: &mut ResolveContext,
+        package_json_path: &Path,
+        target: &Exports,
+        subpath: &str, // The portion that is matched in key pattern, "" if not a pattern match
+        package_subpath: &str,
+        pattern: bool,
+        internal: bool,
+        is_pathmap: bool,
+    ) -> EsResolverResult<Option<PathBuf>> {
+        match target {
+            Exports::String(target) => self.resolve_package_target_string(
+                ctx,
+                package_json_path,
+                target,
+                subpath,
+                package_subpath,
+                pattern,
+                internal,
+                is_pathmap,
+            ),
+            Exports::Object(object) => {
+                for (key, maybe_target) in object.iter() {
+                    let matched = self.condition_enabled(ctx, key);
+
+                    ctx.trace(|| TraceStep::Condition {
+                        condition: key.clone(),
+                        matched,
+                    });
+
+                    if matched {
+                        // An explicit `null` target excludes the subpath.
+                        let Some(target) = maybe_target else {
+                            return Ok(None);
+                        };
+
+                        let result = self.resolve_package_target(
+                            ctx,
+                            package_json_path,
+                            target,
+                            subpath,
+                            package_subpath,
+                            pattern,
+                            internal,
+                            is_pathmap,
+                        )?;
+
+                        match result {
+                            Some(_) => return Ok(result),
+                            _ => continue,
+                        }
+                    }
+                }
+
+                // No condition is matched
+                Ok(None)
+            }
+            Exports::Array(targets) => {
+                let mut last_error = None;
+
+                for target in targets.iter() {
+                    match self.resolve_package_target(
+                        ctx,
+                        package_json_path,
+                        target,
+                        subpath,
+                        package_subpath,
+                        pattern,
+                        internal,
+                        is_pathmap,
+                    ) {
+                        result @ Ok(Some(_)) => return result,
+                        Ok(None) => continue,
+                        Err(e) => last_error = Some(e),
+                    }
+                }
+
+                match last_error {
+                    Some(e) => Err(e),
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip(self, ctx))]
+    fn resolve_package_target_string(
+        &self,
+        ctx: &mut ResolveContext,
+        package_json_path: &Path,
+        target: &str,
+        subpath: &str,
+        package_subpath: &str,
+        pattern: bool,
+        internal: bool,
+        is_pathmap: bool,
+    ) -> EsResolverResult<Option<PathBuf>> {
+        ctx.trace(|| TraceStep::Target(target.to_string()));
+
+        let invalid_target = |reason: String| EsResolverError::InvalidPackageTarget {
+            package_json: package_json_path.to_path_buf(),
+            key: package_subpath.to_string(),
+            target: target.to_string(),
+            reason,
+        };
+
+        if !target.starts_with("./") {
+            if internal && !target.starts_with("../") && !target.starts_with('/') {
+                // A bare specifier in `imports`, e.g. `"#dep": "dep-node-native"`,
+                // is resolved as a package from the package's own directory.
+                let specifier = if pattern {
+                    target.replacen('*', subpath, 1)
+                } else {
+                    target.to_string()
+                };
+
+                debug!(
+                    specifier = format!("{}", specifier),
+                    "matched bare imports target"
+                );
+
+                let package_dir = match package_json_path.parent() {
+                    Some(package_dir) => package_dir,
+                    None => return Ok(None),
+                };
+
+                return match self.load_node_modules(ctx, package_dir, &specifier)? {
+                    Some(p) => Ok(Some(p)),
+                    None => Err(EsResolverError::module_not_found(format!(
+                        "Cannot find package {:?} that {:?} maps to in {}",
+                        specifier,
+                        package_subpath,
+                        package_json_path.to_string_lossy(),
+                    ))),
+                };
+            }
+
+            return Err(invalid_target(String::from(
+                "targets must start with \"./\"",
+            )));
+        }
+
+        if has_invalid_segment(&target[2..]) {
+            return Err(invalid_target(String::from(
+                "targets must not contain \".\", \"..\" or \"node_modules\" segments",
+            )));
+        }
+
+        if pattern && has_invalid_segment(subpath) {
+            return Err(invalid_target(format!(
+                "the subpath {:?} matched by \"*\" must not contain \".\", \"..\" or \"node_modules\" segments",
+                subpath
+            )));
+        }
+
+        let resolved = if !pattern {
+            package_json_path.with_file_name(target)
+        } else {
+            // Only one-star pattern is supported
+            package_json_path.with_file_name(target.replacen('*', subpath, 1))
+        };
+
+        let resolved = resolved.clean();
+
+        if !package_json_path
+            .parent()
+            .is_some_and(|package_dir| resolved.starts_with(package_dir))
+        {
+            return Err(invalid_target(String::from(
+                "targets must stay within the package directory",
+            )));
+        }
+
+        debug!(
+            resolved = format!("{}", resolved.to_string_lossy()),
+            pattern = pattern,
+            "matched target"
+        );
+
+        Ok(Some(resolved))
+    }
+
+    fn try_extension(
+        &self,
+        ctx: &mut ResolveContext,
+        abs_to: &Path,
+        extension: &Extensions,
+    ) -> Option<PathBuf> {
+        let with_extension = add_extension(abs_to, extension.to_str());
+
+        let is_file = self.is_file(&with_extension);
+
+        ctx.trace(|| TraceStep::File {
+            path: with_extension.clean(),
+            found: is_file,
+        });
+
+        if is_file {
+            return Some(with_extension.clean());
+        }
+        None
+    }
+
+    /// Reference:
+    /// 1. <https://github.com/dividab/tsconfig-paths/blob/master/src/tsconfig-loader.ts>
+    fn resolve_tsconfig(
+        &self,
+        ctx: &mut ResolveContext,
+        from_dir: &Path,
+    ) -> EsResolverResult<Option<Arc<TSConfig>>> {
+        let mut maybe_cur_dir = Some(from_dir);
+
+        while let Some(cur_dir) = maybe_cur_dir {
+            for tsconfig_name in TSCONFIG_NAMES {
+                let tsconfig_path = cur_dir.join(tsconfig_name);
+                let maybe_tsconfig = self.parse_tsconfig(&tsconfig_path)?;
+
+                if maybe_tsconfig.is_some() {
+                    debug!(
+                        tsconfig = format!("{}", tsconfig_path.to_string_lossy()),
+                        "tsconfig resolved",
+                    );
+                    ctx.trace(|| TraceStep::TSConfig(tsconfig_path.clone()));
+                    return Ok(maybe_tsconfig);
+                }
+            }
+
+            maybe_cur_dir = cur_dir.parent();
+        }
+
+        debug!("tsconfig is not found");
+
+        Ok(None)
+    }
+
+    fn parse_tsconfig(&self, path: &Path) -> EsResolverResult<Option<Arc<TSConfig>>> {
+        if !self.is_file(path) {
+            return Ok(None);
+        }
+
+        self.cache.tsconfig(path, || self.load_tsconfig(path))
+    }
+
+    fn load_tsconfig(&self, path: &Path) -> EsResolverResult<Option<TSConfig>> {
+        // TODO: what if tsconfig has a ring?
+        if let Ok(content) = self.fs.read_to_string(path) {
+            let stripped = json_comments::StripComments::new(content.as_bytes());
+
+            let mut tsconfig: TSConfig = serde_json::from_reader(stripped)
+                .map_err(|e| EsResolverError::InvalidTSConfig(e, path.to_path_buf()))?;
+
+            tsconfig.compiler_options.base_url = tsconfig
+                .compiler_options
+                .base_url
+                .map(|url| path.with_file_name(url).to_string_lossy().into());
+
+            if let Some(ref extends) = tsconfig.extends {
+                // The tsconfig is cached, so `extends` must not depend on the call that loads it
+                let mut ctx = ResolveContext::for_tsconfig();
+                let extended_tsconfig_path = self
+                    .resolve_impl(&mut ctx, extends, path, /* is_tsconfig */ true)?
+                    .path;
+
+                let maybe_extended_tsconfig = self.parse_tsconfig(&extended_tsconfig_path)?;
+
+                if let Some(extended_tsconfig) = maybe_extended_tsconfig {
+                    tsconfig.compiler_options.base_url = tsconfig
+                        .compiler_options
+                        .base_url
+                        .or_else(|| extended_tsconfig.compiler_options.base_url.clone());
+                    tsconfig.compiler_options.paths = tsconfig
+                        .compiler_options
+                        .paths
+                        .or_else(|| extended_tsconfig.compiler_options.paths.clone());
+
+                    debug!(
+                        "tsconfig extends with {}",
+                        extended_tsconfig_path.to_string_lossy()
+                    );
+                    Ok(Some(tsconfig))
+                } else {
+                    Err(EsResolverError::InvalidTSConfigExtend(format!(
+                        "The 'extends' of {} does not resolve to a valid JSON module. Is the specifier correct?",
+                        path.to_string_lossy()
+                    )))
+                }
+            } else {
+                Ok(Some(tsconfig))
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the matched key in `paths` and the paths to try.
+    #[tracing::instrument(skip(self))]
+    fn match_tsconfig_paths<'p>(
+        &self,
+        target: &'p str,
+        base_url: &str,
+        paths: &'p TSConfigPaths,
+    ) -> (&'p str, Vec<String>) {
+        match paths.get_key_value(target) {
+            // If it is a direct match...
+            Some((key, paths)) => {
+                debug!("mapping {} to constant match {:?}", target, paths);
+
+                let paths = paths
+                    .iter()
+                    .map(|p| Path::new(base_url).join(p).to_string_lossy().into())
+                    .collect();
+
+                (key, paths)
+            }
+            None => {
+                // Now it must be a star match...
+                let mut best_key = "";
+
+                for (key, _) in paths {
+                    if match_exports_pattern(key, target) && pattern_key_compare(best_key, key) == 1
+                    {
+                        best_key = key.as_str();
+                    }
+                }
+
+                // TypeScript implicitly has a `*: [*]` path entry.
+                if best_key.is_empty() {
+                    let path = Path::new(base_url).join(target).to_string_lossy().into();
+
+                    ("*", vec![path])
+                } else {
+                    let best_key_paths = paths.get(best_key).unwrap();
+                    debug!(
+                        "mapping {} to pattern {:?}: {:?}",
+                        target, best_key, best_key_paths
+                    );
+
+                    let paths = best_key_paths
+                        .iter()
+                        .map(|p| {
+                            let extracted = extract_exports_pattern(best_key, target);
+
+                            let path_to_try: String = Path::new(base_url)
+                                .join(p.replacen('*', extracted, 1))
+                                .to_string_lossy()
+                                .into();
+
+                            debug!("trying path {} for {}", path_to_try, target);
+                            path_to_try
+                        })
+                        .collect();
+
+                    (best_key, paths)
+                }
+            }
+        }
+    }
+}
+
+/// Record that `resolved` is matched by `key` in `exports`, or in `imports` when `is_imports`.
+fn matched_by_key(
+    ctx: &mut ResolveContext,
+    resolved: Option<PathBuf>,
+    key: &str,
+    is_imports: bool,
+) -> Option<PathBuf> {
+    if resolved.is_some() {
+        ctx.matched_by = Some(match is_imports {
+            true => MatchedBy::ImportsKey(key.to_string()),
+            false => MatchedBy::ExportsKey(key.to_string()),
+        });
+    }
+
+    resolved
+}
+
+/// Returns: (package_name, package_subpath), where `package_subpath` is what comes after `package_name` after `name`
+pub(crate) fn parse_package_name(name: &str) -> EsResolverResult<(&str, &str)> {
+    let mut sep_index = name.find('/');
+
+    if name.starts_with('@') {
+        match sep_index {
+            Some(i) => {
+                sep_index = name[i + 1..].find('/').map(|j| j + i + 1);
+            }
+            None => {
+                return Err(EsResolverError::InvalidModuleSpecifier(format!("{} is not a valid package name, because it is scoped without a slash. Valid scoped names are like '@babel/core'.", name)));
+            }
+        };
+    }
+
+    let package_name = match sep_index {
+        Some(i) => &name[0..i],
+        None => name,
+    };
+
+    if package_name.starts_with('.') {
+        return Err(EsResolverError::InvalidModuleSpecifier(format!(
+            "{} is not a valid package name, because it starts with a '.'.",
+            name
+        )));
+    }
+
+    if package_name.contains('%') || package_name.contains('\\') {
+        return Err(EsResolverError::InvalidModuleSpecifier(format!(
+            "{} is not a valid package name, because it contains '%' or '\\'.",
+            name
+        )));
+    }
+
+    Ok((package_name, &name[package_name.len()..]))
+}
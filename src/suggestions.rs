@@ -0,0 +1,269 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    file_system::FileSystem,
+    resolver::parse_package_name,
+    trace::{ResolveTrace, TraceStep},
+    types::{Exports, PackageJSON},
+    utils::edit_distance,
+};
+
+/// At most this many suggestions are made, the closest first.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// A "did you mean" hint attached to [`crate::EsResolverError::ModuleNotFound`] and
+/// [`crate::EsResolverError::PackagePathNotExported`] when [`crate::EsResolveOptions::suggestions`] is on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Suggestion {
+    /// A file or directory with a similar name, in a directory that is looked in.
+    SimilarFile(PathBuf),
+    /// A file or directory whose name only differs in case, which is only found
+    /// on a case-insensitive file system.
+    CaseMismatch(PathBuf),
+    /// A package with a similar name in a `node_modules` directory that is looked in.
+    SimilarPackage { name: String, path: PathBuf },
+    /// A subpath in the `exports` of a package.json, similar to the one imported.
+    SimilarExport {
+        package_json: PathBuf,
+        subpath: String,
+    },
+    /// The specifier with the extension or index file it leaves out, e.g. `./foo.js` for `./foo`,
+    /// which is required by [`crate::EsResolveOptions::fully_specified`].
+    FullySpecified(String),
+}
+
+impl Suggestion {
+    fn path(&self) -> Option<&Path> {
+        match self {
+            Suggestion::SimilarFile(path)
+            | Suggestion::CaseMismatch(path)
+            | Suggestion::SimilarPackage { path, .. } => Some(path),
+            Suggestion::SimilarExport { .. } | Suggestion::FullySpecified(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Suggestion::SimilarFile(path) => write!(f, "{}", path.display()),
+            Suggestion::CaseMismatch(path) => write!(f, "{} (differs in case)", path.display()),
+            Suggestion::SimilarPackage { name, .. } => write!(f, "{}", name),
+            Suggestion::SimilarExport {
+                package_json,
+                subpath,
+            } => write!(f, "{:?} exported by {}", subpath, package_json.display()),
+            Suggestion::FullySpecified(specifier) => write!(f, "{:?}", specifier),
+        }
+    }
+}
+
+/// Look for names close to `specifier` in the directories, `node_modules` and `exports`
+/// that are visited in `trace`.
+pub fn suggest(fs: &impl FileSystem, specifier: &str, trace: &ResolveTrace) -> Vec<Suggestion> {
+    let mut probed: BTreeMap<&Path, BTreeSet<&str>> = BTreeMap::new();
+    let mut scored = Vec::new();
+
+    for step in trace.steps.iter() {
+        match step {
+            TraceStep::File { path, found: false } => {
+                if let (Some(dir), Some(name)) = (path.parent(), path.file_name()) {
+                    if let Some(name) = name.to_str() {
+                        probed.entry(dir).or_default().insert(name);
+                    }
+                }
+            }
+            TraceStep::NodeModules(dir) => {
+                if let Some(name) = package_name(specifier) {
+                    similar_packages(fs, dir, name, &mut scored);
+                }
+            }
+            TraceStep::MatchExports {
+                package_json,
+                subpath,
+            } => similar_exports(fs, package_json, subpath, &mut scored),
+            _ => {}
+        }
+    }
+
+    for (dir, names) in probed {
+        similar_files(fs, dir, &names, &mut scored);
+    }
+
+    scored.sort_by_key(|(score, _)| *score);
+
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+
+    for (_, suggestion) in scored {
+        // A package is also a directory in `node_modules`; suggest it only once.
+        let duplicated = suggestions
+            .iter()
+            .any(|s| s == &suggestion || (s.path().is_some() && s.path() == suggestion.path()));
+
+        if !duplicated {
+            suggestions.push(suggestion);
+        }
+    }
+
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// How different `wanted` and `candidate` are, if they are close enough to suggest.
+fn closeness(wanted: &str, candidate: &str) -> Option<usize> {
+    let distance = edit_distance(wanted, candidate);
+
+    (distance <= wanted.chars().count() / 3).then_some(distance)
+}
+
+fn similar_files(
+    fs: &impl FileSystem,
+    dir: &Path,
+    names: &BTreeSet<&str>,
+    scored: &mut Vec<(usize, Suggestion)>,
+) {
+    let Ok(entries) = fs.read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries {
+        let Some(entry_name) = entry.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        // It exists, and is not what is wanted for other reasons, e.g. it is a directory.
+        if names.contains(entry_name) {
+            continue;
+        }
+
+        let lowercase = entry_name.to_lowercase();
+
+        if names.iter().any(|name| name.to_lowercase() == lowercase) {
+            scored.push((0, Suggestion::CaseMismatch(entry)));
+        } else if let Some(score) = names
+            .iter()
+            .filter_map(|name| closeness(stem(name), stem(entry_name)))
+            .min()
+        {
+            scored.push((score, Suggestion::SimilarFile(entry)));
+        }
+    }
+}
+
+fn similar_packages(
+    fs: &impl FileSystem,
+    node_modules: &Path,
+    name: &str,
+    scored: &mut Vec<(usize, Suggestion)>,
+) {
+    let Ok(entries) = fs.read_dir(node_modules) else {
+        return;
+    };
+
+    let mut candidates = Vec::new();
+
+    for entry in entries {
+        let Some(entry_name) = entry.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        if entry_name.starts_with('@') {
+            // Only look into scopes for a scoped package
+            if name.starts_with('@') {
+                for scoped in fs.read_dir(&entry).into_iter().flatten() {
+                    if let Some(scoped_name) = scoped.file_name().and_then(|n| n.to_str()) {
+                        candidates
+                            .push((format!("{}/{}", entry_name, scoped_name), scoped.clone()));
+                    }
+                }
+            }
+        } else if !entry_name.starts_with('.') {
+            candidates.push((entry_name.to_string(), entry.clone()));
+        }
+    }
+
+    for (candidate, path) in candidates {
+        if candidate == name {
+            continue;
+        }
+
+        let score = if candidate.to_lowercase() == name.to_lowercase() {
+            Some(0)
+        } else {
+            closeness(name, &candidate)
+        };
+
+        if let Some(score) = score {
+            scored.push((
+                score,
+                Suggestion::SimilarPackage {
+                    name: candidate,
+                    path,
+                },
+            ));
+        }
+    }
+}
+
+fn similar_exports(
+    fs: &impl FileSystem,
+    package_json_path: &Path,
+    subpath: &str,
+    scored: &mut Vec<(usize, Suggestion)>,
+) {
+    let package_json: Option<PackageJSON> = fs
+        .read_to_string(package_json_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+
+    let Some(PackageJSON {
+        exports: Some(Exports::Object(exports)),
+        ..
+    }) = package_json
+    else {
+        return;
+    };
+
+    for key in exports.keys() {
+        if !key.starts_with('.') || key.contains('*') || key == subpath {
+            continue;
+        }
+
+        let score = if key.to_lowercase() == subpath.to_lowercase() {
+            Some(0)
+        } else {
+            closeness(subpath, key)
+        };
+
+        if let Some(score) = score {
+            scored.push((
+                score,
+                Suggestion::SimilarExport {
+                    package_json: package_json_path.to_path_buf(),
+                    subpath: key.clone(),
+                },
+            ));
+        }
+    }
+}
+
+/// The file name without its last extension.
+fn stem(name: &str) -> &str {
+    match name.rfind('.') {
+        Some(i) if i > 0 => &name[..i],
+        _ => name,
+    }
+}
+
+/// The package name of a bare specifier, e.g. `@scope/name` of `@scope/name/subpath`.
+fn package_name(specifier: &str) -> Option<&str> {
+    if specifier.starts_with('.') || specifier.starts_with('/') || specifier.starts_with('#') {
+        return None;
+    }
+
+    parse_package_name(specifier).ok().map(|(name, _)| name)
+}
@@ -12,10 +12,15 @@ fn package_json() {
         let package_json: PackageJSON = serde_json::from_str(pkg).unwrap();
 
         assert_eq!(package_json, PackageJSON {
+            name: None,
+            version: None,
             main: None,
             module: None,
+            browser: None,
             react_native: None,
-            exports: Some(Exports::String(format!("index.js"))),
+            exports: Some(Exports::String(String::from("index.js"))),
+            imports: None,
+            fields: Default::default(),
         })
     }
 
@@ -45,4 +50,101 @@ fn package_json() {
 
         let _: PackageJSON = serde_json::from_str(pkg).unwrap();
     }
+
+    {
+        let pkg = r#"
+            {
+                "browser": {
+                    "./lib/node.js": "./lib/browser.js",
+                    "fs": false
+                }
+            }
+        "#;
+
+        let package_json: PackageJSON = serde_json::from_str(pkg).unwrap();
+        let browser = match package_json.browser {
+            Some(BrowserField::Object(browser)) => browser,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(
+            browser.get("./lib/node.js"),
+            Some(&BrowserFieldTarget::String(String::from("./lib/browser.js")))
+        );
+        assert_eq!(browser.get("fs"), Some(&BrowserFieldTarget::Bool(false)));
+    }
+
+    {
+        let pkg = r#"
+            {
+                "main": "./index.js",
+                "react-native": "./native.js",
+                "source": "./src/index.ts",
+                "jsnext:main": "./index.mjs",
+                "types": { "not": "a path" }
+            }
+        "#;
+
+        let package_json: PackageJSON = serde_json::from_str(pkg).unwrap();
+
+        assert_eq!(
+            package_json.get_main_field(&MainFields::ReactNative),
+            Some(String::from("./native.js"))
+        );
+        assert_eq!(
+            package_json.get_main_field(&MainFields::from("source")),
+            Some(String::from("./src/index.ts"))
+        );
+        assert_eq!(
+            package_json.get_main_field(&MainFields::from("jsnext:main")),
+            Some(String::from("./index.mjs"))
+        );
+        assert_eq!(
+            package_json.get_main_field(&MainFields::Custom(String::from("main"))),
+            Some(String::from("./index.js"))
+        );
+        assert_eq!(package_json.get_main_field(&MainFields::from("types")), None);
+        assert!(package_json.fields.contains_key("types"));
+    }
+
+    {
+        let pkg = r#"
+            {
+                "main": "./index.js",
+                "module": false,
+                "react-native": { "./lib/node.js": "./lib/native.js" }
+            }
+        "#;
+
+        let package_json: PackageJSON = serde_json::from_str(pkg).unwrap();
+
+        assert_eq!(package_json.main, Some(String::from("./index.js")));
+        assert_eq!(package_json.get_main_field(&MainFields::Module), None);
+        assert_eq!(package_json.get_main_field(&MainFields::ReactNative), None);
+    }
+
+    {
+        let pkg = r#"
+            {
+                "browser": {
+                    "./lib/node.js": "./lib/browser.js",
+                    "./x.js": null,
+                    "./y.js": 1
+                }
+            }
+        "#;
+
+        let package_json: PackageJSON = serde_json::from_str(pkg).unwrap();
+        let browser = match package_json.browser {
+            Some(BrowserField::Object(browser)) => browser,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(browser.len(), 1);
+        assert_eq!(
+            browser.get("./lib/node.js"),
+            Some(&BrowserFieldTarget::String(String::from("./lib/browser.js")))
+        );
+
+        let package_json: PackageJSON = serde_json::from_str(r#"{ "browser": 1 }"#).unwrap();
+        assert_eq!(package_json.browser, None);
+    }
 }
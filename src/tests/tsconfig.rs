@@ -39,9 +39,9 @@ fn tsconfig() {
             TSConfig {
                 extends: None,
                 compiler_options: TSConfigCompilerOptions {
-                    base_url: Some(format!(".")),
+                    base_url: Some(String::from(".")),
                     paths: Some(indexmap! {
-                        format!("@/*") => vec![format!("root/*")],
+                        String::from("@/*") => vec![String::from("root/*")],
                     }),
                 }
             }
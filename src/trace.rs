@@ -0,0 +1,132 @@
+use std::{fmt, path::PathBuf};
+
+use crate::types::MainFields;
+
+/// Every step taken by [`crate::Resolver::resolve_with_trace`], in order.
+///
+/// Its [`fmt::Display`] prints one step per line, which reads as a report of what was tried.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ResolveTrace {
+    pub steps: Vec<TraceStep>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TraceStep {
+    /// The tsconfig.json whose `compilerOptions.paths` apply.
+    TSConfig(PathBuf),
+    /// The key of `compilerOptions.paths` matching the specifier, and the paths it maps to.
+    TSConfigPaths { key: String, paths: Vec<String> },
+    /// A `node_modules` directory, or another one of [`crate::EsResolveOptions::modules`], is visited.
+    NodeModules(PathBuf),
+    /// The `exports` of a package.json are matched against `subpath`, e.g. `"./feature"`.
+    MatchExports {
+        package_json: PathBuf,
+        subpath: String,
+    },
+    /// The `imports` of a package.json are matched against `specifier`, e.g. `"#utils"`.
+    MatchImports {
+        package_json: PathBuf,
+        specifier: String,
+    },
+    /// The key of `exports` or `imports` that matches.
+    MatchedKey(String),
+    /// A condition in `exports` or `imports`, and whether it is one of
+    /// [`crate::EsResolveOptions::conditions`].
+    Condition { condition: String, matched: bool },
+    /// A target in `exports` or `imports`.
+    Target(String),
+    /// A main field of package.json and its value.
+    MainField { field: MainFields, path: String },
+    /// The specifier is replaced with `target` by the `key` of [`crate::EsResolveOptions::alias`].
+    Alias { key: String, target: String },
+    /// The specifier that is not found is replaced with `target` by the `key` of
+    /// [`crate::EsResolveOptions::fallback`].
+    Fallback { key: String, target: String },
+    /// The Node built-in module `name` is replaced with `target` by
+    /// [`crate::BrowserBuiltins::Polyfill`].
+    Polyfill { name: String, target: String },
+    /// The specifier is replaced with `target` by the `pattern` of
+    /// [`crate::EsResolveOptions::module_name_mapper`].
+    ModuleNameMapper { pattern: String, target: String },
+    /// `key` is replaced by the `browser` field of a package.json.
+    BrowserField { package_json: PathBuf, key: String },
+    /// A file is probed, with or without an extension.
+    File { path: PathBuf, found: bool },
+}
+
+impl ResolveTrace {
+    pub(crate) fn push(&mut self, step: TraceStep) {
+        self.steps.push(step);
+    }
+}
+
+impl fmt::Display for ResolveTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in self.steps.iter() {
+            writeln!(f, "{}", step)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceStep::TSConfig(path) => write!(f, "using tsconfig {}", path.display()),
+            TraceStep::TSConfigPaths { key, paths } => {
+                write!(f, "paths key {:?} maps to {:?}", key, paths)
+            }
+            TraceStep::NodeModules(path) => write!(f, "looking in {}", path.display()),
+            TraceStep::MatchExports {
+                package_json,
+                subpath,
+            } => write!(
+                f,
+                "matching {:?} against the exports of {}",
+                subpath,
+                package_json.display()
+            ),
+            TraceStep::MatchImports {
+                package_json,
+                specifier,
+            } => write!(
+                f,
+                "matching {:?} against the imports of {}",
+                specifier,
+                package_json.display()
+            ),
+            TraceStep::MatchedKey(key) => write!(f, "matched key {:?}", key),
+            TraceStep::Condition { condition, matched } => match matched {
+                true => write!(f, "condition {:?} is enabled", condition),
+                false => write!(f, "condition {:?} is skipped", condition),
+            },
+            TraceStep::Target(target) => write!(f, "trying target {:?}", target),
+            TraceStep::MainField { field, path } => {
+                write!(f, "main field {:?} is {:?}", field.name(), path)
+            }
+            TraceStep::Alias { key, target } => {
+                write!(f, "aliased to {:?} by {:?}", target, key)
+            }
+            TraceStep::Fallback { key, target } => {
+                write!(f, "falls back to {:?} by {:?}", target, key)
+            }
+            TraceStep::Polyfill { name, target } => {
+                write!(f, "{:?} is polyfilled with {:?}", name, target)
+            }
+            TraceStep::ModuleNameMapper { pattern, target } => {
+                write!(f, "mapped to {:?} by {:?}", target, pattern)
+            }
+            TraceStep::BrowserField { package_json, key } => write!(
+                f,
+                "{:?} is replaced by the browser field of {}",
+                key,
+                package_json.display()
+            ),
+            TraceStep::File { path, found: true } => write!(f, "found {}", path.display()),
+            TraceStep::File { path, found: false } => {
+                write!(f, "{} does not exist", path.display())
+            }
+        }
+    }
+}
@@ -1,14 +1,54 @@
+use std::{fmt, path::PathBuf};
+
 use indexmap::IndexMap;
-use serde::Deserialize;
+use path_clean::PathClean;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
 
-use crate::data::DEFAULT_EXTENSIONS;
+use crate::{
+    data::{
+        DEFAULT_EXTENSIONS, NODE_MODULES, NODE_POLYFILLS, TYPESCRIPT_DECLARATION_EXTENSION_ALIAS,
+        TYPESCRIPT_EXTENSION_ALIAS,
+    },
+    suggestions::Suggestion,
+    trace::ResolveTrace,
+};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum MainFields {
     Main,
     Module,
-    //    Browser,
+    /// The string form of `browser`. The object form is [`EsResolveOptions::browser_field`].
+    Browser,
+    /// `react-native`
     ReactNative,
+    /// Any other field whose value is a path, e.g. `source`, `style`, `types` or `jsnext:main`.
+    Custom(String),
+}
+
+impl MainFields {
+    /// The field named `name`, which is a [`MainFields::Custom`] unless it has its own variant.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from(name: &str) -> MainFields {
+        match name {
+            "main" => MainFields::Main,
+            "module" => MainFields::Module,
+            "browser" => MainFields::Browser,
+            "react-native" => MainFields::ReactNative,
+            _ => MainFields::Custom(name.to_string()),
+        }
+    }
+
+    /// The name of the field in package.json.
+    pub fn name(&self) -> &str {
+        match self {
+            MainFields::Main => "main",
+            MainFields::Module => "module",
+            MainFields::Browser => "browser",
+            MainFields::ReactNative => "react-native",
+            MainFields::Custom(name) => name,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -17,17 +57,68 @@ pub enum TargetEnv {
     Browser,
 }
 
+/// A version of Node as `(major, minor, patch)`, e.g. `(22, 5, 0)` for v22.5.0.
+pub type NodeVersion = (u32, u32, u32);
+
+/// How a specifier is imported, which is passed to [`crate::Resolver::resolve_with_kind`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ResolveKind {
+    /// `import x from 'specifier'`
+    Import,
+    /// `require('specifier')`
+    Require,
+    /// `import('specifier')`
+    DynamicImport,
+    /// `@import 'specifier'` in a stylesheet
+    CssImport,
+    /// `url('specifier')` in a stylesheet, or `new URL('specifier', import.meta.url)`
+    Url,
+}
+
+impl ResolveKind {
+    /// The conditions in `exports` and `imports` decided by how a specifier is imported.
+    /// Among these, only the ones of this kind are enabled.
+    pub(crate) const CONDITIONS: [&'static str; 3] = ["import", "require", "style"];
+
+    /// The conditions enabled by this kind, in addition to [`EsResolveOptions::conditions`].
+    pub fn conditions(&self) -> &'static [&'static str] {
+        match self {
+            ResolveKind::Import | ResolveKind::DynamicImport => &["import"],
+            ResolveKind::Require => &["require"],
+            ResolveKind::CssImport => &["style"],
+            ResolveKind::Url => &[],
+        }
+    }
+
+    /// Whether it is loaded as an ES module, for which Node neither appends an
+    /// extension nor looks for the index file of a directory.
+    /// See [`EsResolveOptions::fully_specified`].
+    pub fn is_esm(&self) -> bool {
+        matches!(self, ResolveKind::Import | ResolveKind::DynamicImport)
+    }
+
+    /// Whether a bare specifier, like `@import 'theme.css'`, is tried as a relative path first,
+    /// as stylesheets do.
+    pub fn prefers_relative(&self) -> bool {
+        matches!(self, ResolveKind::CssImport | ResolveKind::Url)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EsResolveOptions {
     /// The priority by which the index file of package.json is resolved.
+    /// Any other field, like `source`, is a [`MainFields::Custom`].
     /// 
     /// By default:
     /// 
     /// for [`TargetEnv::Node`], is `vec![MainFields::Main, MainFields::Module]`.
     /// 
-    /// for [`TargetEnv::Browser`] is `vec![MainFields::Module, MainFields::Main]`.
+    /// for [`TargetEnv::Browser`] is `vec![MainFields::Browser, MainFields::Module, MainFields::Main]`.
     pub main_fields: Vec<MainFields>,
     /// The priority the conditions in `exports` follows, which mimics how NodeJS handles [conditonal exports](https://nodejs.org/api/packages.html#conditional-exports).
+    ///
+    /// With [`crate::Resolver::resolve_with_kind`], `"import"`, `"require"` and `"style"` are
+    /// enabled by the [`ResolveKind`] instead.
     /// 
     /// By default:
     /// 
@@ -36,14 +127,173 @@ pub struct EsResolveOptions {
     /// for [`TargetEnv::Browser`] is `vec![format!("browser"), format!("module"), format!("import"), format!("default")`.
     pub conditions: Vec<String>,
     /// The priority of file extensions that files are found.
+    /// Any other extension, like `vue` or `module.css`, is an [`Extensions::Custom`].
     /// 
     /// Default: `[Extensions::Tsx, Extensions::Ts, Extensions::Jsx, Extensions::Js, Extensions::Css, Extensions::Json]`
     pub extensions: Vec<Extensions>,
+    /// The extensions tried in place of the one a specifier ends with, in order, like webpack's
+    /// [`resolve.extensionAlias`](https://webpack.js.org/configuration/resolve/#resolveextensionalias).
+    /// They are tried after the path as written and the [`EsResolveOptions::extensions`] appended to it.
+    ///
+    /// Default: [`EsResolveOptions::typescript_extension_alias`] without declarations, i.e.
+    /// `.js` and `.jsx` to `.ts` and `.tsx`, `.mjs` to `.mts`, and `.cjs` to `.cts`.
+    pub extension_alias: Vec<(Extensions, Vec<Extensions>)>,
+    /// The names of the index file of a directory, in order, which are tried with each of
+    /// [`EsResolveOptions::extensions`], like webpack's [`resolve.mainFiles`](https://webpack.js.org/configuration/resolve/#resolvemainfiles).
+    ///
+    /// Default: `vec![format!("index")]`
+    pub main_files: Vec<String>,
+    /// The directories that packages are looked in, like webpack's [`resolve.modules`](https://webpack.js.org/configuration/resolve/#resolvemodules).
+    ///
+    /// A name, like `node_modules` or `web_modules`, is looked for in the directory imported from
+    /// and every ancestor of it, in order. An absolute directory, like `/app/src`, is only looked in
+    /// after all of them, which allows imports like `import 'components/button'`.
+    ///
+    /// See also [`crate::Resolver::lookup_paths`].
+    ///
+    /// Default: `vec![format!("node_modules")]`
+    pub modules: Vec<String>,
+    /// The directories looked in at last, after [`EsResolveOptions::modules`], like the
+    /// [global folders](https://nodejs.org/api/modules.html#loading-from-the-global-folders) of Node
+    /// that [`EsResolveOptions::node_global_folders`] reads from the environment.
+    ///
+    /// Default: `vec![]`
+    pub global_folders: Vec<PathBuf>,
+    /// Replace a specifier, or the start of it, before it is resolved, like webpack's
+    /// [`resolve.alias`](https://webpack.js.org/configuration/resolve/#resolvealias).
+    ///
+    /// A key like `"react"` matches `react` and `react/jsx-runtime`, while `"react$"` only matches
+    /// `react`. The first key that matches is replaced with each of its targets in order, until
+    /// one resolves. A relative target like `"./src"` is resolved from
+    /// [`EsResolveOptions::root_dir`], not from the file imported from.
+    ///
+    /// Default: `vec![]`
+    pub alias: Vec<(String, Vec<AliasTarget>)>,
+    /// Replace a bare specifier that is not found in any of [`EsResolveOptions::modules`], like webpack's
+    /// [`resolve.fallback`](https://webpack.js.org/configuration/resolve/#resolvefallback), which
+    /// matches like [`EsResolveOptions::alias`]. For example, `"crypto"` to `"crypto-browserify"`,
+    /// or `"stream"` to [`AliasTarget::Ignore`] for a [`TargetEnv::Browser`] build.
+    ///
+    /// Default: `vec![]`
+    pub fallback: Vec<(String, Vec<AliasTarget>)>,
+    /// Replace a specifier that matches a regular expression, like Jest's
+    /// [`moduleNameMapper`](https://jestjs.io/docs/configuration#modulenamemapper-objectstring-string--arraystring),
+    /// which applies after [`EsResolveOptions::alias`].
+    ///
+    /// The first pattern that matches is replaced with each of its targets in order, until one
+    /// resolves. `$1` in a target stands for the first capture group, and `<rootDir>` for
+    /// [`EsResolveOptions::root_dir`]. A target is not mapped again.
+    ///
+    /// Default: `vec![]`
+    pub module_name_mapper: Vec<(Regex, Vec<String>)>,
+    /// The directory `<rootDir>` stands for in [`EsResolveOptions::module_name_mapper`], and that
+    /// relative targets of [`EsResolveOptions::alias`] and [`EsResolveOptions::fallback`] are
+    /// resolved from. When `None`, it is the directory of the package.json closest to the file
+    /// imported from.
+    ///
+    /// Default: `None`
+    pub root_dir: Option<PathBuf>,
+    /// Whether a package with an `exports` field only exposes the subpaths it exports, as Node does.
+    /// A subpath that is not exported, or exported as `null`, fails with [`EsResolverError::PackagePathNotExported`].
+    ///
+    /// When `false`, such a subpath is loaded from the package directory as if there were no `exports`.
+    ///
+    /// Default: `true`
+    pub strict_exports: bool,
+    /// Whether the object form of `browser` in package.json remaps files and packages,
+    /// following the [browser field spec](https://github.com/defunctzombie/package-browser-field-spec).
+    /// A file or a package mapped to `false` resolves to [`ResolutionKind::Ignored`].
+    ///
+    /// The string form of `browser` is controlled by [`MainFields::Browser`] instead.
+    ///
+    /// Default: `false` for [`TargetEnv::Node`], `true` for [`TargetEnv::Browser`].
+    pub browser_field: bool,
+    /// Whether a relative specifier, or one within a package, must name the file with its extension,
+    /// as Node requires for [ES modules](https://nodejs.org/api/esm.html#mandatory-file-extensions).
+    /// No extension is appended, and no index file of a directory is loaded, except for the main field
+    /// of a package. `import './foo'` fails with a [`Suggestion`] of `./foo.js` if that exists.
+    ///
+    /// This is always the case for [`ResolveKind::Import`] and [`ResolveKind::DynamicImport`]
+    /// with [`TargetEnv::Node`].
+    ///
+    /// Default: `false`
+    pub fully_specified: bool,
+    /// Whether [`EsResolveOptions::extension_alias`] still applies to a fully specified path,
+    /// as TypeScript does, e.g. `./foo.js` loads `./foo.ts` when only the latter exists.
+    /// See [`EsResolveOptions::fully_specified`].
+    ///
+    /// Default: `false`
+    pub fully_specified_rewrites: bool,
+    /// What a Node built-in module, like `path` or `node:path`, resolves to for [`TargetEnv::Browser`]
+    /// when no package of the same name is found. It is ignored for [`TargetEnv::Node`].
+    ///
+    /// Default: [`BrowserBuiltins::Error`]
+    pub browser_builtins: BrowserBuiltins,
+    /// The version of Node targeted, which decides the built-in modules there are, e.g. `node:sqlite`
+    /// since `(22, 5, 0)`. A module that is newer is resolved like any other package.
+    ///
+    /// Default: `None`, i.e. all the built-in modules known.
+    pub node_version: Option<NodeVersion>,
+    /// The packages left to the runtime or another bundle, which resolve to [`ResolutionKind::External`]
+    /// as written, e.g. peer dependencies of a library. A specifier that matches any of them is
+    /// external, unless it is relative, absolute, a `#specifier` or a Node built-in module.
+    ///
+    /// Default: `vec![]`
+    pub externals: Vec<Externals>,
+    /// Whether an external package has to be found in [`EsResolveOptions::modules`], and
+    /// is resolved as usual otherwise. Its package.json is then the [`Resolution::package`].
+    ///
+    /// Default: `false`
+    pub verify_externals: bool,
+    /// Whether [`EsResolverError::ModuleNotFound`] and [`EsResolverError::PackagePathNotExported`]
+    /// suggest similar files, packages and exported subpaths. The places visited are traced to
+    /// list their directories, which costs a little even when the module is found.
+    ///
+    /// A missing extension required by [`EsResolveOptions::fully_specified`] is always suggested.
+    ///
+    /// Default: `false`
+    pub suggestions: bool,
 }
 
 impl EsResolveOptions {
     pub fn default_extensions() -> Vec<Extensions> {
-        return Vec::from(DEFAULT_EXTENSIONS);
+        Vec::from(DEFAULT_EXTENSIONS)
+    }
+
+    /// How TypeScript maps the extension of an import to the file it is compiled from, which is
+    /// followed by the declaration file, like `.d.ts` for `.js`, when `declarations` are wanted.
+    pub fn typescript_extension_alias(declarations: bool) -> Vec<(Extensions, Vec<Extensions>)> {
+        let alias = match declarations {
+            true => TYPESCRIPT_DECLARATION_EXTENSION_ALIAS,
+            false => TYPESCRIPT_EXTENSION_ALIAS,
+        };
+
+        alias
+            .iter()
+            .map(|(extension, aliases)| (extension.clone(), aliases.to_vec()))
+            .collect()
+    }
+
+    /// The global folders of Node, i.e. each directory in the `NODE_PATH` environment variable,
+    /// followed by `$HOME/.node_modules` and `$HOME/.node_libraries`.
+    ///
+    /// See <https://nodejs.org/api/modules.html#loading-from-the-global-folders>.
+    pub fn node_global_folders() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = match std::env::var_os("NODE_PATH") {
+            Some(node_path) => std::env::split_paths(&node_path)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        if let Some(home) = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+            let home = PathBuf::from(home);
+
+            dirs.push(home.join(".node_modules"));
+            dirs.push(home.join(".node_libraries"));
+        }
+
+        dirs
     }
 
     pub fn default_for(env: TargetEnv) -> Self {
@@ -52,9 +302,26 @@ impl EsResolveOptions {
                 main_fields: vec![MainFields::Main, MainFields::Module], // Node.js itself doesn't respect "module"
                 conditions: vec![format!("node"), format!("require"), format!("default")],
                 extensions: Self::default_extensions(),
+                extension_alias: Self::typescript_extension_alias(false),
+                main_files: vec![format!("index")],
+                modules: vec![NODE_MODULES.to_string()],
+                global_folders: vec![],
+                alias: Vec::new(),
+                fallback: Vec::new(),
+                module_name_mapper: Vec::new(),
+                root_dir: None,
+                strict_exports: true,
+                browser_field: false,
+                fully_specified: false,
+                fully_specified_rewrites: false,
+                browser_builtins: BrowserBuiltins::Error,
+                node_version: None,
+                externals: Vec::new(),
+                verify_externals: false,
+                suggestions: false,
             },
             TargetEnv::Browser => Self {
-                main_fields: vec![MainFields::Module, MainFields::Main],
+                main_fields: vec![MainFields::Browser, MainFields::Module, MainFields::Main],
                 conditions: vec![
                     format!("browser"),
                     format!("module"),
@@ -62,33 +329,392 @@ impl EsResolveOptions {
                     format!("default"),
                 ],
                 extensions: Self::default_extensions(),
+                extension_alias: Self::typescript_extension_alias(false),
+                main_files: vec![format!("index")],
+                modules: vec![NODE_MODULES.to_string()],
+                global_folders: vec![],
+                alias: Vec::new(),
+                fallback: Vec::new(),
+                module_name_mapper: Vec::new(),
+                root_dir: None,
+                strict_exports: true,
+                browser_field: true,
+                fully_specified: false,
+                fully_specified_rewrites: false,
+                browser_builtins: BrowserBuiltins::Error,
+                node_version: None,
+                externals: Vec::new(),
+                verify_externals: false,
+                suggestions: false,
             },
         }
     }
 }
 
-/// Any errors that might occur during [`crate::EsResolver::resolve`]
+/// A rule of [`EsResolveOptions::externals`].
+#[derive(Clone, Debug)]
+pub enum Externals {
+    /// The specifier is exactly this, e.g. `react`, which does not match `react/jsx-runtime`.
+    Exact(String),
+    /// The specifier starts with this, e.g. `@babel/` or `lodash/`.
+    Prefix(String),
+    /// The specifier matches this.
+    Regex(Regex),
+    /// The package of the specifier is one of the `dependencies` or `peerDependencies` of the
+    /// package.json closest to the file imported from, e.g. `react/jsx-runtime` for `react`.
+    Dependencies,
+}
+
+/// See [`EsResolveOptions::browser_builtins`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BrowserBuiltins {
+    /// Fail with [`EsResolverError::NodeBuiltinInBrowser`].
+    Error,
+    /// Resolve the module with what it is mapped to, e.g. [`BrowserBuiltins::default_polyfills`].
+    /// A module that is not mapped fails with [`EsResolverError::NodeBuiltinInBrowser`].
+    Polyfill(Vec<(String, AliasTarget)>),
+    /// Leave the module to the runtime as a [`ResolutionKind::NodeBuiltin`], e.g. `node:path`.
+    External,
+    /// Resolve to [`ResolutionKind::Ignored`], i.e. an empty module.
+    Empty,
+}
+
+impl BrowserBuiltins {
+    /// The polyfills webpack 4 used to include, e.g. `path` to `path-browserify` and `fs` to an empty module.
+    pub fn default_polyfills() -> Vec<(String, AliasTarget)> {
+        NODE_POLYFILLS
+            .iter()
+            .map(|(name, polyfill)| {
+                let target = match polyfill {
+                    Some(polyfill) => AliasTarget::Specifier(polyfill.to_string()),
+                    None => AliasTarget::Ignore,
+                };
+
+                (name.to_string(), target)
+            })
+            .collect()
+    }
+}
+
+/// What a key of [`EsResolveOptions::alias`] or [`EsResolveOptions::fallback`] is replaced with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AliasTarget {
+    /// A specifier that is resolved in place of the key, e.g. `preact/compat` or `/app/src`.
+    Specifier(String),
+    /// `false` in webpack, which resolves to [`ResolutionKind::Ignored`].
+    Ignore,
+}
+
+/// Any errors that might occur during [`crate::Resolver::resolve`] or [`crate::EsResolver::resolve`]
 #[derive(Debug)]
 pub enum EsResolverError {
     /// Fail to resolve the target because unable to load a critical file,
-    /// e.g. that [`crate::EsResolver::from`] is not a real file.
+    /// e.g. that the file resolved from is not a real file.
     IOError(std::io::Error, String),
     /// Fail to read a package.json. When `LOAD_PACKAGE_EXPORTS` is assumpted but
     /// the package.json is invalid, this is raised in accordance to Node's behavior.
-    InvalidPackageJSON(serde_json::Error),
+    InvalidPackageJSON(serde_json::Error, PathBuf),
     /// Fail to read a tsconfig.json
-    InvalidTSConfig(serde_json::Error),
+    InvalidTSConfig(serde_json::Error, PathBuf),
+    /// The `extends` of a tsconfig.json does not resolve to a tsconfig.json.
     InvalidTSConfigExtend(String),
     /// When `LOAD_PACKAGE_EXPORTS`, the exports field is found invalid.
     /// See <https://nodejs.org/api/packages.html#subpath-exports>.
     InvalidExports(String),
+    /// A target in `exports` or `imports` is invalid, e.g. it does not start with `./`,
+    /// or it points outside of the package directory.
+    /// See <https://nodejs.org/api/esm.html#resolver-algorithm-specification>.
+    InvalidPackageTarget {
+        package_json: PathBuf,
+        /// The key in `exports` or `imports` that is matched
+        key: String,
+        target: String,
+        reason: String,
+    },
+    /// The package has an `exports` field, but `subpath` is not exported by it, or is exported as `null`.
+    /// See <https://nodejs.org/api/packages.html#subpath-exports>.
+    PackagePathNotExported {
+        package: String,
+        subpath: String,
+        /// Exported subpaths similar to `subpath`. See [`EsResolveOptions::suggestions`].
+        suggestions: Vec<Suggestion>,
+    },
+    /// The specifier is not a valid package name or `#specifier`.
     InvalidModuleSpecifier(String),
-    ModuleNotFound(String),
+    /// A `#specifier` is not defined in the `imports` of the nearest package.json.
+    /// See <https://nodejs.org/api/packages.html#subpath-imports>.
+    PackageImportNotDefined(String),
+    /// A `node:` specifier is not a built-in module of [`EsResolveOptions::node_version`].
+    UnknownBuiltinModule(String),
+    /// A Node built-in module is imported for [`TargetEnv::Browser`] without a polyfill.
+    /// See [`EsResolveOptions::browser_builtins`].
+    NodeBuiltinInBrowser {
+        name: String,
+        from: PathBuf,
+        /// The polyfill that is configured but not found, or the one webpack 4 used.
+        polyfill: Option<String>,
+    },
+    /// Nothing is found for the specifier. `trace` is only filled by [`crate::Resolver::resolve_with_trace`].
+    ModuleNotFound {
+        message: String,
+        trace: ResolveTrace,
+        /// Similar files and packages in the places looked in. See [`EsResolveOptions::suggestions`].
+        suggestions: Vec<Suggestion>,
+    },
+}
+
+impl EsResolverError {
+    pub(crate) fn module_not_found(message: String) -> Self {
+        EsResolverError::ModuleNotFound {
+            message,
+            trace: ResolveTrace::default(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// A stable code to branch on, following the [error codes of Node](https://nodejs.org/api/errors.html#nodejs-error-codes)
+    /// where Node has one, e.g. `"ERR_MODULE_NOT_FOUND"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EsResolverError::IOError(..) => "ERR_IO",
+            EsResolverError::InvalidPackageJSON(..) | EsResolverError::InvalidExports(_) => {
+                "ERR_INVALID_PACKAGE_CONFIG"
+            }
+            EsResolverError::InvalidTSConfig(..) => "ERR_INVALID_TSCONFIG",
+            EsResolverError::InvalidTSConfigExtend(_) => "ERR_INVALID_TSCONFIG_EXTENDS",
+            EsResolverError::InvalidPackageTarget { .. } => "ERR_INVALID_PACKAGE_TARGET",
+            EsResolverError::PackagePathNotExported { .. } => "ERR_PACKAGE_PATH_NOT_EXPORTED",
+            EsResolverError::InvalidModuleSpecifier(_) => "ERR_INVALID_MODULE_SPECIFIER",
+            EsResolverError::PackageImportNotDefined(_) => "ERR_PACKAGE_IMPORT_NOT_DEFINED",
+            EsResolverError::UnknownBuiltinModule(_) => "ERR_UNKNOWN_BUILTIN_MODULE",
+            EsResolverError::NodeBuiltinInBrowser { .. } => "ERR_NODE_BUILTIN_IN_BROWSER",
+            EsResolverError::ModuleNotFound { .. } => "ERR_MODULE_NOT_FOUND",
+        }
+    }
+}
+
+/// Writes `. Did you mean a, b or c?` if there is any suggestion.
+fn write_suggestions(f: &mut fmt::Formatter<'_>, suggestions: &[Suggestion]) -> fmt::Result {
+    match suggestions {
+        [] => Ok(()),
+        [only] => write!(f, ". Did you mean {}?", only),
+        [init @ .., last] => {
+            write!(f, ". Did you mean ")?;
+
+            for (i, suggestion) in init.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", suggestion)?;
+            }
+
+            write!(f, " or {}?", last)
+        }
+    }
+}
+
+/// The error that causes an error, e.g. the [`std::io::Error`] of [`EsResolverError::IOError`],
+/// is not part of the message but returned by [`std::error::Error::source`].
+impl fmt::Display for EsResolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EsResolverError::IOError(_, message)
+            | EsResolverError::InvalidTSConfigExtend(message)
+            | EsResolverError::InvalidExports(message)
+            | EsResolverError::InvalidModuleSpecifier(message)
+            | EsResolverError::PackageImportNotDefined(message) => write!(f, "{}", message),
+            EsResolverError::InvalidPackageJSON(_, path) => {
+                write!(f, "Invalid package.json at {}", path.display())
+            }
+            EsResolverError::InvalidTSConfig(_, path) => {
+                write!(f, "Invalid tsconfig.json at {}", path.display())
+            }
+            EsResolverError::InvalidPackageTarget {
+                package_json,
+                key,
+                target,
+                reason,
+            } => write!(
+                f,
+                "Invalid target {:?} for {:?} in {}: {}",
+                target,
+                key,
+                package_json.display(),
+                reason
+            ),
+            EsResolverError::PackagePathNotExported {
+                package,
+                subpath,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "Package subpath {:?} is not defined by \"exports\" of package {}",
+                    subpath, package
+                )?;
+                write_suggestions(f, suggestions)
+            }
+            EsResolverError::UnknownBuiltinModule(specifier) => {
+                write!(f, "No such built-in module: {}", specifier)
+            }
+            EsResolverError::NodeBuiltinInBrowser {
+                name,
+                from,
+                polyfill,
+            } => {
+                write!(
+                    f,
+                    "Cannot resolve the Node built-in module {:?} from {} in the browser",
+                    name,
+                    from.display()
+                )?;
+
+                match polyfill {
+                    Some(polyfill) => write!(f, " without the polyfill {:?}", polyfill),
+                    None => Ok(()),
+                }
+            }
+            EsResolverError::ModuleNotFound {
+                message,
+                suggestions,
+                ..
+            } => {
+                write!(f, "{}", message)?;
+                write_suggestions(f, suggestions)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EsResolverError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EsResolverError::IOError(e, _) => Some(e),
+            EsResolverError::InvalidPackageJSON(e, _) | EsResolverError::InvalidTSConfig(e, _) => {
+                Some(e)
+            }
+            _ => None,
+        }
+    }
 }
 
 pub type EsResolverResult<T> = Result<T, EsResolverError>;
 
-#[derive(Debug, Clone)]
+/// What a specifier resolves to. See [`crate::Resolver::resolve`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Resolution {
+    /// The absolute path to the resolved module.
+    /// For [`ResolutionKind::NodeBuiltin`], this is the `node:`-prefixed module name instead.
+    /// For [`ResolutionKind::Ignored`], this is the ignored file, or the package name if a package is ignored.
+    /// For [`ResolutionKind::External`], this is the specifier as written.
+    pub path: PathBuf,
+    pub kind: ResolutionKind,
+    /// The query of the specifier including the leading `?`, e.g. `?raw` of `./logo.svg?raw`.
+    pub query: Option<String>,
+    /// The fragment of the specifier including the leading `#`, e.g. `#hash` of `./logo.svg#hash`.
+    pub fragment: Option<String>,
+    /// The package the resolved file belongs to, i.e. the nearest package.json not above `node_modules`.
+    /// For [`ResolutionKind::External`], this is the package found by [`EsResolveOptions::verify_externals`].
+    pub package: Option<PackageInfo>,
+    /// How the file is found. `None` for [`ResolutionKind::NodeBuiltin`].
+    pub matched_by: Option<MatchedBy>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ResolutionKind {
+    /// A module on the file system.
+    File,
+    /// A Node built-in module, like `node:fs`.
+    NodeBuiltin,
+    /// A module mapped to `false` by the `browser` field of package.json,
+    /// which should be replaced with an empty module.
+    Ignored,
+    /// A module in [`EsResolveOptions::externals`], which is not bundled.
+    External {
+        /// The name of the package, e.g. `react` of `react/jsx-runtime`.
+        package: String,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PackageInfo {
+    /// The path to the package.json
+    pub package_json: PathBuf,
+    pub name: Option<String>,
+    pub version: Option<String>,
+}
+
+/// The mechanism that finds the resolved file. When several take part, e.g. a `paths`
+/// mapping in tsconfig.json followed by extension probing, this is the outermost one.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MatchedBy {
+    /// The path is an existing file as written.
+    ExactPath,
+    /// An extension is appended to the path, or replaces the one written.
+    Extension(Extensions),
+    /// The index file of a directory, one of [`EsResolveOptions::main_files`].
+    IndexFile,
+    /// A main field of package.json.
+    MainField(MainFields),
+    /// A key of `exports` in package.json, e.g. `"./*"`.
+    ExportsKey(String),
+    /// A key of `imports` in package.json, e.g. `"#utils/*"`.
+    ImportsKey(String),
+    /// A key of `compilerOptions.paths` in tsconfig.json, or `"*"` for the implicit mapping
+    /// relative to `baseUrl`.
+    TSConfigPaths(String),
+    /// A key of the `browser` field in package.json.
+    BrowserField(String),
+    /// A key of [`EsResolveOptions::alias`], e.g. `"react$"`.
+    Alias(String),
+    /// A key of [`EsResolveOptions::fallback`], e.g. `"crypto"`.
+    Fallback(String),
+    /// A Node built-in module mapped by [`BrowserBuiltins::Polyfill`], e.g. `"path"`.
+    Polyfill(String),
+    /// A pattern of [`EsResolveOptions::module_name_mapper`], e.g. `"^@/(.*)$"`.
+    ModuleNameMapper(String),
+}
+
+impl Resolution {
+    pub(crate) fn file(path: PathBuf) -> Self {
+        Self::new(path.clean(), ResolutionKind::File)
+    }
+
+    pub(crate) fn node_builtin(name: &str) -> Self {
+        Self::new(PathBuf::from(name), ResolutionKind::NodeBuiltin)
+    }
+
+    pub(crate) fn ignored(path: PathBuf) -> Self {
+        Self::new(path.clean(), ResolutionKind::Ignored)
+    }
+
+    pub(crate) fn external(specifier: &str, package: &str) -> Self {
+        Self::new(
+            PathBuf::from(specifier),
+            ResolutionKind::External {
+                package: package.to_string(),
+            },
+        )
+    }
+
+    fn new(path: PathBuf, kind: ResolutionKind) -> Self {
+        Self {
+            path,
+            kind,
+            query: None,
+            fragment: None,
+            package: None,
+            matched_by: None,
+        }
+    }
+
+    /// The resolved path as a string, which is what [`crate::EsResolver::resolve`] returns.
+    pub fn to_string_lossy(&self) -> String {
+        self.path.to_string_lossy().into()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Extensions {
     Mjs,
     Mts,
@@ -101,10 +727,27 @@ pub enum Extensions {
     Tsx,
     Node,
     Css,
+    /// `.d.ts`
+    Dts,
+    /// `.d.mts`
+    Dmts,
+    /// `.d.cts`
+    Dcts,
+    /// Any other extension without the leading `.`, e.g. `vue` or `module.css`.
+    Custom(String),
 }
 
 impl Extensions {
+    /// The extension named `ext`, with or without the leading `.`, e.g. `"vue"` or `".d.ts"`.
+    /// `None` if it is empty or contains a path separator.
+    #[allow(clippy::should_implement_trait)]
     pub fn from(ext: &str) -> Option<Extensions> {
+        let ext = ext.strip_prefix('.').unwrap_or(ext);
+
+        if ext.is_empty() || ext.contains(['/', '\\']) || ext.ends_with('.') {
+            return None;
+        }
+
         match ext {
             "mjs" => Some(Extensions::Mjs),
             "mts" => Some(Extensions::Mts),
@@ -117,11 +760,16 @@ impl Extensions {
             "tsx" => Some(Extensions::Tsx),
             "node" => Some(Extensions::Node),
             "css" => Some(Extensions::Css),
-            _ => None,
+            "d.ts" => Some(Extensions::Dts),
+            "d.mts" => Some(Extensions::Dmts),
+            "d.cts" => Some(Extensions::Dcts),
+            _ => Some(Extensions::Custom(ext.to_string())),
         }
     }
 
-    pub fn to_str(&self) -> &'static str {
+    /// The extension without the leading `.`, which [`Extensions::from`] turns back into `self`,
+    /// unless `self` is a [`Extensions::Custom`] one that has its own variant.
+    pub fn to_str(&self) -> &str {
         match self {
             Extensions::Mjs => "mjs",
             Extensions::Mts => "mts",
@@ -134,6 +782,10 @@ impl Extensions {
             Extensions::Tsx => "tsx",
             Extensions::Node => "node",
             Extensions::Css => "css",
+            Extensions::Dts => "d.ts",
+            Extensions::Dmts => "d.mts",
+            Extensions::Dcts => "d.cts",
+            Extensions::Custom(ext) => ext,
         }
     }
 }
@@ -141,14 +793,55 @@ impl Extensions {
 #[derive(Deserialize, Debug, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct PackageJSON {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    #[serde(default, deserialize_with = "main_field")]
     pub main: Option<String>,
+    #[serde(default, deserialize_with = "main_field")]
     pub module: Option<String>,
-    // Browser field needs special treatment:
-    // Temporarily removed.
-    // https://github.com/defunctzombie/package-browser-field-spec
-    // pub browser: Option<String>,
+    #[serde(default, deserialize_with = "browser_field")]
+    pub browser: Option<BrowserField>,
+    #[serde(rename = "react-native", default, deserialize_with = "main_field")]
     pub react_native: Option<String>,
     pub exports: Option<Exports>,
+    /// See <https://nodejs.org/api/packages.html#subpath-imports>.
+    pub imports: Option<IndexMap<String, Option<Exports>>>,
+    /// Every other field as written, e.g. `source` or `types`, which [`MainFields::Custom`] reads.
+    #[serde(flatten)]
+    pub fields: IndexMap<String, serde_json::Value>,
+}
+
+/// A main field that is not a path, e.g. an object `react-native` or `"module": false`,
+/// is left out rather than failing the whole package.json.
+fn main_field<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(path) => Ok(Some(path)),
+        _ => Ok(None),
+    }
+}
+
+/// Likewise, a `browser` that is neither a path nor an object is left out, and so is an entry
+/// of the object that is neither a path nor a boolean, e.g. `{ "./lib/node.js": null }`.
+fn browser_field<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<BrowserField>, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(path) => Ok(Some(BrowserField::String(path))),
+        serde_json::Value::Object(map) => Ok(Some(BrowserField::Object(
+            map.into_iter()
+                .filter_map(|(key, target)| match target {
+                    serde_json::Value::String(path) => {
+                        Some((key, BrowserFieldTarget::String(path)))
+                    }
+                    serde_json::Value::Bool(enabled) => {
+                        Some((key, BrowserFieldTarget::Bool(enabled)))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        ))),
+        _ => Ok(None),
+    }
 }
 
 impl PackageJSON {
@@ -156,10 +849,31 @@ impl PackageJSON {
         match field {
             MainFields::Main => self.main.clone(),
             MainFields::Module => self.module.clone(),
-            // MainFields::Browser => self.browser.clone(),
+            MainFields::Browser => match self.browser {
+                Some(BrowserField::String(ref browser)) => Some(browser.clone()),
+                _ => None,
+            },
             MainFields::ReactNative => self.react_native.clone(),
+            MainFields::Custom(name) => match MainFields::from(name) {
+                MainFields::Custom(_) => self
+                    .fields
+                    .get(name)
+                    .and_then(|value| value.as_str())
+                    .map(String::from),
+                field => self.get_main_field(&field),
+            },
         }
     }
+
+    /// Whether `name` is one of the `dependencies` or `peerDependencies`.
+    pub fn has_dependency(&self, name: &str) -> bool {
+        ["dependencies", "peerDependencies"].iter().any(|field| {
+            self.fields
+                .get(*field)
+                .and_then(|dependencies| dependencies.as_object())
+                .is_some_and(|dependencies| dependencies.contains_key(name))
+        })
+    }
 }
 
 #[derive(Deserialize, Debug, Eq, PartialEq)]
@@ -170,6 +884,26 @@ pub enum Exports {
     Array(Vec<Exports>),
 }
 
+/// See <https://github.com/defunctzombie/package-browser-field-spec>.
+#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum BrowserField {
+    /// Replaces `main`, e.g. `"browser": "./browser.js"`.
+    String(String),
+    /// Maps files (keys starting with `.`) and packages to their replacements,
+    /// e.g. `{ "./lib/node.js": "./lib/browser.js", "fs": false }`.
+    Object(IndexMap<String, BrowserFieldTarget>),
+}
+
+#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum BrowserFieldTarget {
+    /// A file relative to the package.json, or another package.
+    String(String),
+    /// `false` ignores the module.
+    Bool(bool),
+}
+
 #[derive(Deserialize, Debug, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TSConfig {
@@ -178,20 +912,12 @@ pub struct TSConfig {
     pub compiler_options: TSConfigCompilerOptions,
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Default, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TSConfigCompilerOptions {
     pub base_url: Option<String>,
     pub paths: Option<TSConfigPaths>,
 }
 
-impl Default for TSConfigCompilerOptions {
-    fn default() -> Self {
-        TSConfigCompilerOptions {
-            base_url: None,
-            paths: None,
-        }
-    }
-}
 
 pub type TSConfigPaths = IndexMap<String, Vec<String>>;
@@ -1,10 +1,16 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use regex::Captures;
 
 pub fn match_exports_pattern(pattern: &str, target: &str) -> bool {
     let star_index = pattern.find('*');
 
     match star_index {
-        Some(i) => target.starts_with(&pattern[0..i]) && target.ends_with(&pattern[i + 1..]),
+        Some(i) => {
+            target.len() >= pattern.len()
+                && target.starts_with(&pattern[0..i])
+                && target.ends_with(&pattern[i + 1..])
+        }
         None => pattern == target,
     }
 }
@@ -36,13 +42,9 @@ pub fn pattern_key_compare(a: &str, b: &str) -> isize {
 
     if base_len_a > base_len_b {
         -1
-    } else if base_len_b > base_len_a {
-        1
-    } else if a_pattern_index == usize::MAX {
+    } else if base_len_b > base_len_a || a_pattern_index == usize::MAX {
         1
-    } else if b_pattern_index == usize::MAX {
-        -1
-    } else if a.len() > b.len() {
+    } else if b_pattern_index == usize::MAX || a.len() > b.len() {
         -1
     } else if b.len() > a.len() {
         1
@@ -51,10 +53,7 @@ pub fn pattern_key_compare(a: &str, b: &str) -> isize {
     }
 }
 
-pub fn add_extension(
-    path: &PathBuf,
-    extension: impl AsRef<std::path::Path>,
-) -> PathBuf {
+pub fn add_extension(path: &Path, extension: impl AsRef<Path>) -> PathBuf {
     match path.extension() {
         Some(ext) => {
             let mut p = PathBuf::from(path);
@@ -62,8 +61,136 @@ pub fn add_extension(
             ext.push(".");
             ext.push(extension.as_ref());
             p.set_extension(ext);
-            return p;
+            p
         }
         None => path.with_extension(extension.as_ref()),
     }
 }
+
+/// `path` without `extension`, e.g. `foo` of `foo.js` or `foo.d` of `foo.d.js`.
+/// `None` if `path` does not end with `extension`.
+pub fn strip_extension(path: &Path, extension: &str) -> Option<PathBuf> {
+    let file_name = path.file_name()?.to_str()?;
+    let stem = file_name.strip_suffix(extension)?.strip_suffix('.')?;
+
+    match stem.is_empty() {
+        true => None,
+        false => Some(path.with_file_name(stem)),
+    }
+}
+
+/// Whether `path` has a `.`, `..` or `node_modules` segment, which a package target must not have.
+/// Percent-encoded segments like `%2e%2e` are treated as what they encode, as Node does.
+///
+/// Reference: `invalidSegmentRegEx` in <https://github.com/nodejs/node/blob/main/lib/internal/modules/esm/resolve.js>
+pub fn has_invalid_segment(path: &str) -> bool {
+    path.split(['/', '\\']).any(|segment| {
+        let segment = percent_decode(segment).to_ascii_lowercase();
+        segment == "." || segment == ".." || segment == "node_modules"
+    })
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// `template` with each `$1`, `$2` and so on replaced by that capture group of `captures`, or
+/// nothing if the group did not match, as Jest does for `moduleNameMapper`.
+pub fn expand_captures(template: &str, captures: &Captures) -> String {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(i) = rest.find('$') {
+        expanded.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        match rest[..digits].parse::<usize>() {
+            Ok(group) => {
+                if let Some(m) = captures.get(group) {
+                    expanded.push_str(m.as_str());
+                }
+            }
+            Err(_) => expanded.push('$'),
+        }
+
+        rest = &rest[digits..];
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Split `specifier` into its path, query and fragment, e.g. `./a.svg?raw#top` into
+/// `("./a.svg", Some("?raw"), Some("#top"))`. The leading `#` of an imports specifier is
+/// not taken as a fragment.
+pub fn split_query_fragment(specifier: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (rest, fragment) = match specifier.char_indices().skip(1).find(|(_, c)| *c == '#') {
+        Some((i, _)) => (&specifier[..i], Some(&specifier[i..])),
+        None => (specifier, None),
+    };
+
+    match rest.find('?') {
+        Some(i) => (&rest[..i], Some(&rest[i..]), fragment),
+        None => (rest, None, fragment),
+    }
+}
+
+/// The optimal string alignment distance between `a` and `b`, i.e. the number of insertions,
+/// deletions, substitutions and transpositions of adjacent characters needed to turn one into the other.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // d[i][j] is the distance between a[..i] and b[..j]
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
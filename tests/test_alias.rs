@@ -0,0 +1,286 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::test_util::{resolver, with_tracing};
+    use es_resolve::*;
+
+    fn fs() -> MemoryFileSystem {
+        MemoryFileSystem::from_json(
+            r##"
+            {
+                "/app": {
+                    "package.json": {},
+                    "src/index.js": "",
+                    "src/deep/x.js": "",
+                    "src/components/button.tsx": "",
+                    "node_modules/preact": {
+                        "package.json": {
+                            "exports": {
+                                ".": "./dist/preact.js",
+                                "./compat": "./compat/dist/compat.js",
+                                "./jsx-runtime": "./jsx-runtime/dist/jsx.js"
+                            }
+                        },
+                        "dist/preact.js": "",
+                        "compat/dist/compat.js": "",
+                        "jsx-runtime/dist/jsx.js": ""
+                    },
+                    "node_modules/lodash/index.js": "",
+                    "node_modules/lodash/get.js": "",
+                    "node_modules/lodash-es/index.js": "",
+                    "node_modules/react/index.js": "",
+                    "node_modules/react/compat.js": "",
+                    "node_modules/crypto-browserify/index.js": "",
+                    "node_modules/path-browserify/index.js": "",
+                    "node_modules/buffer/index.js": "",
+                    "node_modules/some-lib/index.js": ""
+                }
+            }
+            "##,
+        )
+        .unwrap()
+    }
+
+    fn aliases(entries: Vec<(&str, Vec<AliasTarget>)>) -> Vec<(String, Vec<AliasTarget>)> {
+        entries
+            .into_iter()
+            .map(|(key, targets)| (key.to_string(), targets))
+            .collect()
+    }
+
+    fn alias(entries: Vec<(&str, Vec<AliasTarget>)>) -> Resolver<MemoryFileSystem> {
+        resolver(TargetEnv::Browser, fs(), |options| {
+            options.alias = aliases(entries)
+        })
+    }
+
+    fn fallback(entries: Vec<(&str, Vec<AliasTarget>)>) -> Resolver<MemoryFileSystem> {
+        resolver(TargetEnv::Browser, fs(), |options| {
+            options.fallback = aliases(entries)
+        })
+    }
+
+    fn specifier(s: &str) -> AliasTarget {
+        AliasTarget::Specifier(s.to_string())
+    }
+
+    #[test]
+    fn prefix() {
+        with_tracing(|| {
+            let r = alias(vec![
+                ("react", vec![specifier("preact/compat")]),
+                ("react/jsx-runtime", vec![specifier("preact/jsx-runtime")]),
+                ("@", vec![specifier("/app/src")]),
+            ]);
+            let s = Path::new("/app/src/index.js");
+
+            let resolution = r.resolve("react", s).unwrap();
+            assert_eq!(
+                resolution.path,
+                Path::new("/app/node_modules/preact/compat/dist/compat.js")
+            );
+            assert_eq!(
+                resolution.matched_by,
+                Some(MatchedBy::Alias(String::from("react")))
+            );
+            // The first key that matches wins
+            assert!(r.resolve("react/jsx-runtime", s).is_err());
+            assert_eq!(
+                r.resolve("@/components/button", s).unwrap().path,
+                Path::new("/app/src/components/button.tsx")
+            );
+            // Only whole path segments match
+            assert!(r.resolve("@babel/core", s).is_err());
+            assert_eq!(
+                r.resolve("./components/button", s).unwrap().matched_by,
+                Some(MatchedBy::Extension(Extensions::Tsx))
+            );
+        });
+    }
+
+    #[test]
+    fn relative_targets() {
+        with_tracing(|| {
+            let r = alias(vec![("@", vec![specifier("./src")])]);
+
+            // From the package root, not the file imported from
+            for from in ["/app/src/index.js", "/app/src/deep/x.js"] {
+                assert_eq!(
+                    r.resolve("@/components/button", Path::new(from))
+                        .unwrap()
+                        .path,
+                    Path::new("/app/src/components/button.tsx")
+                );
+            }
+
+            let r = resolver(TargetEnv::Browser, fs(), |options| {
+                options.alias = aliases(vec![("ui", vec![specifier("../components")])]);
+                options.root_dir = Some(PathBuf::from("/app/src/deep"));
+            });
+            let (resolution, trace) = r
+                .resolve_with_trace("ui/button", Path::new("/app/src/index.js"))
+                .unwrap();
+            assert_eq!(resolution.path, Path::new("/app/src/components/button.tsx"));
+            assert!(trace.steps.contains(&TraceStep::Alias {
+                key: String::from("ui"),
+                target: String::from("/app/src/components/button"),
+            }));
+        });
+    }
+
+    #[test]
+    fn exact() {
+        with_tracing(|| {
+            let r = alias(vec![("lodash$", vec![specifier("lodash-es")])]);
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve("lodash", s).unwrap().path,
+                Path::new("/app/node_modules/lodash-es/index.js")
+            );
+            assert_eq!(
+                r.resolve("lodash/get", s).unwrap().path,
+                Path::new("/app/node_modules/lodash/get.js")
+            );
+        });
+    }
+
+    #[test]
+    fn ignore_and_fallbacks() {
+        with_tracing(|| {
+            let r = alias(vec![
+                ("fs", vec![AliasTarget::Ignore]),
+                (
+                    "ui",
+                    vec![specifier("/app/lib/ui"), specifier("/app/src/components")],
+                ),
+                ("missing", vec![specifier("/app/lib/missing")]),
+            ]);
+            let s = Path::new("/app/src/index.js");
+
+            let resolution = r.resolve("fs", s).unwrap();
+            assert_eq!(resolution.kind, ResolutionKind::Ignored);
+            assert_eq!(resolution.path, Path::new("fs"));
+            assert_eq!(
+                r.resolve("ui/button", s).unwrap().path,
+                Path::new("/app/src/components/button.tsx")
+            );
+            assert!(matches!(
+                r.resolve("missing", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn recursive() {
+        with_tracing(|| {
+            let r = alias(vec![
+                ("react", vec![specifier("react/compat")]),
+                ("ui", vec![specifier("@/components")]),
+                ("@", vec![specifier("/app/src")]),
+            ]);
+            let s = Path::new("/app/src/index.js");
+
+            // Not aliased again
+            assert_eq!(
+                r.resolve("react", s).unwrap().path,
+                Path::new("/app/node_modules/react/compat.js")
+            );
+
+            let (resolution, trace) = r.resolve_with_trace("ui/button", s).unwrap();
+            assert_eq!(resolution.path, Path::new("/app/src/components/button.tsx"));
+            // The outermost alias
+            assert_eq!(
+                resolution.matched_by,
+                Some(MatchedBy::Alias(String::from("ui")))
+            );
+            assert!(trace.steps.contains(&TraceStep::Alias {
+                key: String::from("@"),
+                target: String::from("/app/src/components/button"),
+            }));
+        });
+    }
+
+    #[test]
+    fn fallbacks() {
+        with_tracing(|| {
+            let r = fallback(vec![
+                ("crypto", vec![specifier("crypto-browserify")]),
+                ("stream", vec![AliasTarget::Ignore]),
+                (
+                    "path",
+                    vec![specifier("path-polyfill"), specifier("path-browserify")],
+                ),
+                ("buffer", vec![specifier("buffer-polyfill")]),
+            ]);
+            let s = Path::new("/app/node_modules/some-lib/index.js");
+
+            let (resolution, trace) = r.resolve_with_trace("crypto", s).unwrap();
+            assert_eq!(
+                resolution.path,
+                Path::new("/app/node_modules/crypto-browserify/index.js")
+            );
+            assert_eq!(
+                resolution.matched_by,
+                Some(MatchedBy::Fallback(String::from("crypto")))
+            );
+            assert!(trace.steps.contains(&TraceStep::Fallback {
+                key: String::from("crypto"),
+                target: String::from("crypto-browserify"),
+            }));
+
+            let resolution = r.resolve("stream", s).unwrap();
+            assert_eq!(resolution.kind, ResolutionKind::Ignored);
+            assert_eq!(
+                r.resolve("path", s).unwrap().path,
+                Path::new("/app/node_modules/path-browserify/index.js")
+            );
+            // Only when the package is not found
+            assert_eq!(
+                r.resolve("buffer", s).unwrap().path,
+                Path::new("/app/node_modules/buffer/index.js")
+            );
+            assert!(matches!(
+                r.resolve("./crypto", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+            assert!(matches!(
+                r.resolve("left-pad", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn recursive_fallbacks() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Browser, fs(), |options| {
+                options.fallback = aliases(vec![
+                    ("util", vec![specifier("util/")]),
+                    ("crypto", vec![specifier("@polyfills")]),
+                ]);
+                options.alias = aliases(vec![(
+                    "@polyfills",
+                    vec![specifier("/app/node_modules/crypto-browserify")],
+                )]);
+            });
+            let s = Path::new("/app/src/index.js");
+
+            assert!(r.resolve("util", s).is_err());
+
+            let resolution = r.resolve("crypto", s).unwrap();
+            assert_eq!(
+                resolution.path,
+                Path::new("/app/node_modules/crypto-browserify/index.js")
+            );
+            assert_eq!(
+                resolution.matched_by,
+                Some(MatchedBy::Fallback(String::from("crypto")))
+            );
+        });
+    }
+}
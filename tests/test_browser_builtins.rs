@@ -0,0 +1,178 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver(browser_builtins: BrowserBuiltins) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(
+            TargetEnv::Browser,
+            MemoryFileSystem::from_files([
+                ("/app/src/index.js", ""),
+                ("/app/node_modules/path-browserify/index.js", ""),
+                ("/app/node_modules/events/events.js", ""),
+                (
+                    "/app/node_modules/events/package.json",
+                    r#"{ "main": "./events.js" }"#,
+                ),
+                ("/app/node_modules/util/util.js", ""),
+                (
+                    "/app/node_modules/util/package.json",
+                    r#"{ "main": "./util.js" }"#,
+                ),
+            ]),
+            |options| {
+                options.browser_builtins = browser_builtins;
+            },
+        )
+    }
+
+    #[test]
+    fn error() {
+        with_tracing(|| {
+            let r = resolver(BrowserBuiltins::Error);
+            let s = Path::new("/app/src/index.js");
+
+            let e = r.resolve("path", s).unwrap_err();
+            assert!(matches!(
+                &e,
+                EsResolverError::NodeBuiltinInBrowser { name, polyfill, .. }
+                    if name == "path" && polyfill.as_deref() == Some("path-browserify")
+            ));
+            assert_eq!(e.code(), "ERR_NODE_BUILTIN_IN_BROWSER");
+            assert_eq!(
+                e.to_string(),
+                "Cannot resolve the Node built-in module \"path\" from /app/src/index.js \
+                 in the browser without the polyfill \"path-browserify\""
+            );
+            assert_eq!(
+                r.resolve("node:fs", s).unwrap_err().to_string(),
+                "Cannot resolve the Node built-in module \"fs\" from /app/src/index.js in the browser"
+            );
+            // A package of the same name is taken first
+            assert_eq!(
+                r.resolve("events", s).unwrap().path,
+                Path::new("/app/node_modules/events/events.js")
+            );
+            assert!(matches!(
+                r.resolve("not-a-builtin", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn polyfill() {
+        with_tracing(|| {
+            let r = resolver(BrowserBuiltins::Polyfill(
+                BrowserBuiltins::default_polyfills(),
+            ));
+            let s = Path::new("/app/src/index.js");
+
+            let (resolution, trace) = r.resolve_with_trace("node:path", s).unwrap();
+            assert_eq!(
+                resolution.path,
+                Path::new("/app/node_modules/path-browserify/index.js")
+            );
+            assert_eq!(
+                resolution.matched_by,
+                Some(MatchedBy::Polyfill(String::from("path")))
+            );
+            assert!(trace.steps.contains(&TraceStep::Polyfill {
+                name: String::from("path"),
+                target: String::from("path-browserify"),
+            }));
+
+            assert_eq!(
+                r.resolve("node:util", s).unwrap().path,
+                Path::new("/app/node_modules/util/util.js")
+            );
+            assert_eq!(r.resolve("fs", s).unwrap().kind, ResolutionKind::Ignored);
+            // Configured, but not installed
+            assert!(matches!(
+                r.resolve("crypto", s),
+                Err(EsResolverError::NodeBuiltinInBrowser { polyfill: Some(polyfill), .. })
+                    if polyfill == "crypto-browserify"
+            ));
+            // Not configured
+            assert!(matches!(
+                r.resolve("worker_threads", s),
+                Err(EsResolverError::NodeBuiltinInBrowser { polyfill: None, .. })
+            ));
+
+            let r = resolver(BrowserBuiltins::Polyfill(vec![(
+                String::from("os"),
+                AliasTarget::Specifier(String::from("os")),
+            )]));
+            assert!(matches!(
+                r.resolve("os", s),
+                Err(EsResolverError::NodeBuiltinInBrowser { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn polyfill_with_exports() {
+        with_tracing(|| {
+            let r = test_util::resolver(
+                TargetEnv::Browser,
+                MemoryFileSystem::from_json(
+                    r##"
+                    {
+                        "/app": {
+                            "src/index.js": "",
+                            "node_modules/util": {
+                                "package.json": {
+                                    "exports": { ".": "./util.js", "./support/*": "./support/*.js" }
+                                },
+                                "util.js": ""
+                            }
+                        }
+                    }
+                    "##,
+                )
+                .unwrap(),
+                |options| {
+                    options.browser_builtins =
+                        BrowserBuiltins::Polyfill(BrowserBuiltins::default_polyfills());
+                },
+            );
+            let s = Path::new("/app/src/index.js");
+
+            for specifier in ["util", "sys", "node:sys"] {
+                assert_eq!(
+                    r.resolve(specifier, s).unwrap().path,
+                    Path::new("/app/node_modules/util/util.js")
+                );
+            }
+            assert_eq!(
+                r.resolve("sys", s).unwrap().matched_by,
+                Some(MatchedBy::Polyfill(String::from("sys")))
+            );
+        });
+    }
+
+    #[test]
+    fn external_and_empty() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/index.js");
+
+            let r = resolver(BrowserBuiltins::External);
+            let resolution = r.resolve("path", s).unwrap();
+            assert_eq!(resolution.kind, ResolutionKind::NodeBuiltin);
+            assert_eq!(resolution.path, Path::new("node:path"));
+            assert_eq!(
+                r.resolve("node:fs/promises", s).unwrap().path,
+                Path::new("node:fs/promises")
+            );
+
+            let r = resolver(BrowserBuiltins::Empty);
+            let resolution = r.resolve("node:crypto", s).unwrap();
+            assert_eq!(resolution.kind, ResolutionKind::Ignored);
+            assert_eq!(resolution.path, Path::new("node:crypto"));
+        });
+    }
+}
@@ -0,0 +1,117 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, path::Path};
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver() -> Resolver<MemoryFileSystem> {
+        test_util::resolver(
+            TargetEnv::Node,
+            MemoryFileSystem::from_json(
+                r##"
+                {
+                    "/app": {
+                        "package.json": {
+                            "imports": { "#utils": "./src/utils.js" }
+                        },
+                        "src/index.js": "",
+                        "src/utils.js": "",
+                        "node_modules/pkg/package.json": {
+                            "exports": { ".": "./index.js", "./feature": "./feature.js" }
+                        },
+                        "node_modules/pkg/index.js": "",
+                        "node_modules/pkg/feature.js": "",
+                        "node_modules/broken/package.json": "{ \"exports\": "
+                    }
+                }
+                "##,
+            )
+            .unwrap(),
+            |options| options.suggestions = true,
+        )
+    }
+
+    #[test]
+    fn codes() {
+        with_tracing(|| {
+            let r = resolver();
+            let s = Path::new("/app/src/index.js");
+            let code = |specifier: &str| r.resolve(specifier, s).unwrap_err().code();
+
+            assert_eq!(code("./missing"), "ERR_MODULE_NOT_FOUND");
+            assert_eq!(code("pkg/internal"), "ERR_PACKAGE_PATH_NOT_EXPORTED");
+            assert_eq!(code("#missing"), "ERR_PACKAGE_IMPORT_NOT_DEFINED");
+            assert_eq!(code("#/utils"), "ERR_INVALID_MODULE_SPECIFIER");
+            assert_eq!(code("broken"), "ERR_INVALID_PACKAGE_CONFIG");
+            assert_eq!(
+                r.resolve("./utils", Path::new("/app/src/missing.js"))
+                    .unwrap_err()
+                    .code(),
+                "ERR_IO"
+            );
+        });
+    }
+
+    #[test]
+    fn display() {
+        with_tracing(|| {
+            let r = resolver();
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve("./utlis", s).unwrap_err().to_string(),
+                "Cannot resolve \"./utlis\" from /app/src/index.js. Did you mean /app/src/utils.js?"
+            );
+            assert_eq!(
+                r.resolve("pkg/featuer", s).unwrap_err().to_string(),
+                "Package subpath \"./featuer\" is not defined by \"exports\" of package pkg. \
+                 Did you mean \"./feature\" exported by /app/node_modules/pkg/package.json?"
+            );
+            assert_eq!(
+                r.resolve("broken", s).unwrap_err().to_string(),
+                "Invalid package.json at /app/node_modules/broken/package.json"
+            );
+        });
+    }
+
+    #[test]
+    fn source() {
+        with_tracing(|| {
+            let r = resolver();
+            let s = Path::new("/app/src/index.js");
+
+            let invalid = r.resolve("broken", s).unwrap_err();
+            assert!(invalid
+                .source()
+                .is_some_and(|e| e.is::<serde_json::Error>()));
+
+            let io = r
+                .resolve("./utils", Path::new("/app/src/missing.js"))
+                .unwrap_err();
+            assert!(io.source().is_some_and(|e| e.is::<std::io::Error>()));
+
+            assert!(r.resolve("./missing", s).unwrap_err().source().is_none());
+        });
+    }
+
+    #[test]
+    fn boxed() {
+        fn resolve(r: &Resolver<MemoryFileSystem>, specifier: &str) -> Result<(), Box<dyn Error>> {
+            r.resolve(specifier, Path::new("/app/src/index.js"))?;
+            Ok(())
+        }
+
+        with_tracing(|| {
+            let r = resolver();
+
+            assert!(resolve(&r, "pkg/feature").is_ok());
+            assert_eq!(
+                resolve(&r, "#missing").unwrap_err().to_string(),
+                "Package import specifier \"#missing\" is not defined in /app/package.json imported from /app/src/index.js"
+            );
+        });
+    }
+}
@@ -6,18 +6,17 @@ mod tests {
 
     use crate::test_util::with_tracing;
     use es_resolve::*;
-    use tracing::Level;
 
     fn source(s: &str) -> PathBuf {
-        return PathBuf::from("tests")
+        PathBuf::from("tests")
             .join("examples")
             .join(s)
             .canonicalize()
-            .unwrap();
+            .unwrap()
     }
 
     fn source_str(s: &str) -> String {
-        return source(s).to_string_lossy().into();
+        source(s).to_string_lossy().into()
     }
 
     #[test]
@@ -2,21 +2,21 @@ mod test_util;
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
-    use crate::test_util::with_tracing;
+    use crate::test_util::{self, with_tracing, RecordingFileSystem};
     use es_resolve::*;
 
     fn source(s: &str) -> PathBuf {
-        return PathBuf::from("tests")
+        PathBuf::from("tests")
             .join("fixtures")
             .join(s)
             .canonicalize()
-            .unwrap();
+            .unwrap()
     }
 
     fn source_str(s: &str) -> String {
-        return source(s).to_string_lossy().into();
+        source(s).to_string_lossy().into()
     }
 
     #[test]
@@ -82,11 +82,18 @@ mod tests {
         );
 
         // pkg.browser for browser
-        // let r = EsResolver::new("./package_json_browser", &s, TargetEnv::Browser);
-        // assert_eq!(
-        //     r.resolve().unwrap(),
-        //     source_str("directory/package_json_browser/browser.js")
-        // );
+        let r = EsResolver::new("./package_json_browser", &s, TargetEnv::Browser);
+        assert_eq!(
+            r.resolve().unwrap(),
+            source_str("directory/package_json_browser/browser.js")
+        );
+
+        // pkg.main for node
+        let r = EsResolver::new("./package_json_browser", &s, TargetEnv::Node);
+        assert_eq!(
+            r.resolve().unwrap(),
+            source_str("directory/package_json_browser/main.js")
+        );
 
         // implicit package/index.js
         let r = EsResolver::new("./package_json_missing_main", &s, TargetEnv::Browser);
@@ -336,4 +343,96 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn resolver_reuse() {
+        let resolver = Resolver::new(TargetEnv::Browser);
+
+        let s = source("relative/index.js");
+        let r = resolver.resolve("./ts", &s).unwrap();
+        assert_eq!(r.path, source("relative/ts.ts"));
+        assert_eq!(r.kind, ResolutionKind::File);
+
+        // Same resolver, different source file and cached package.json
+        let s = source("node_modules_/import_exports.mjs");
+        for _ in 0..2 {
+            let r = resolver.resolve("exports/nest2", &s).unwrap();
+            assert_eq!(
+                r.path,
+                source("node_modules_/node_modules/exports/nest1/nest2/index.mjs")
+            );
+        }
+
+        let s = source("tspaths/tsconfig-extends/relative/index.ts");
+        let r = resolver.resolve("constant", &s).unwrap();
+        assert_eq!(r.path, source("tspaths/tsconfig-extends/relative/constant.ts"));
+
+        let resolver = Resolver::new(TargetEnv::Node);
+        let r = resolver.resolve("fs", &s).unwrap();
+        assert_eq!(r.to_string_lossy(), "node:fs");
+        assert_eq!(r.kind, ResolutionKind::NodeBuiltin);
+    }
+
+    #[test]
+    fn file_system() {
+        let s = source("node_modules_/import_exports.mjs");
+        let resolver = test_util::resolver(
+            TargetEnv::Browser,
+            RecordingFileSystem::new(OsFileSystem),
+            |_| {},
+        );
+
+        let r = resolver.resolve("exports", &s).unwrap();
+        assert_eq!(r.path, source("node_modules_/node_modules/exports/index.mjs"));
+
+        let fs = resolver.file_system();
+        assert_eq!(
+            fs.reads_of(source("node_modules_/node_modules/exports/package.json")),
+            1
+        );
+    }
+
+    #[test]
+    fn cached_reads() {
+        with_tracing(|| {
+            let fs = MemoryFileSystem::from_json(
+                r#"
+                {
+                    "/app": {
+                        "src/index.ts": "",
+                        "tsconfig.json": {
+                            "compilerOptions": { "baseUrl": ".", "paths": { "@/*": ["src/*"] } }
+                        },
+                        "node_modules/pkg": {
+                            "package.json": { "main": "./main.js" },
+                            "main.js": ""
+                        }
+                    }
+                }
+                "#,
+            )
+            .unwrap();
+            let r = test_util::resolver(TargetEnv::Node, RecordingFileSystem::new(fs), |_| {});
+            let s = Path::new("/app/src/index.ts");
+
+            for _ in 0..2 {
+                assert_eq!(
+                    r.resolve("pkg", s).unwrap().path,
+                    Path::new("/app/node_modules/pkg/main.js")
+                );
+            }
+
+            let fs = r.file_system();
+            assert_eq!(fs.reads_of("/app/node_modules/pkg/package.json"), 1);
+            assert_eq!(fs.reads_of("/app/tsconfig.json"), 1);
+            assert_eq!(fs.canonicalized_of(s), 1);
+
+            r.clear_cache();
+            r.resolve("pkg", s).unwrap();
+
+            assert_eq!(fs.reads_of("/app/node_modules/pkg/package.json"), 2);
+            assert_eq!(fs.reads_of("/app/tsconfig.json"), 2);
+            assert_eq!(fs.canonicalized_of(s), 2);
+        });
+    }
 }
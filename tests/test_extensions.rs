@@ -0,0 +1,208 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver(
+        env: TargetEnv,
+        configure: impl FnOnce(&mut EsResolveOptions),
+    ) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(
+            env,
+            MemoryFileSystem::from_files([
+                ("/app/src/index.ts", ""),
+                ("/app/src/component.tsx", ""),
+                ("/app/src/esm.mts", ""),
+                ("/app/src/cjs.cts", ""),
+                ("/app/src/typed.d.ts", ""),
+                ("/app/src/typed.d.mts", ""),
+                ("/app/src/config.d.cts", ""),
+                ("/app/src/style.scss", ""),
+                ("/app/src/App.vue", ""),
+                ("/app/src/Button.svelte", ""),
+                ("/app/src/button.module.css", ""),
+                ("/app/src/legacy.d.ts", ""),
+                ("/app/src/widget/index.web.tsx", ""),
+                ("/app/src/widget/index.ts", ""),
+                ("/app/src/deno/mod.ts", ""),
+            ]),
+            configure,
+        )
+    }
+
+    #[test]
+    fn extension_alias() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Node, |_| {});
+            let s = Path::new("/app/src/index.ts");
+
+            assert_eq!(
+                r.resolve("./component.js", s).unwrap().path,
+                Path::new("/app/src/component.tsx")
+            );
+            assert_eq!(
+                r.resolve("./esm.mjs", s).unwrap().path,
+                Path::new("/app/src/esm.mts")
+            );
+            assert_eq!(
+                r.resolve("./cjs.cjs", s).unwrap().path,
+                Path::new("/app/src/cjs.cts")
+            );
+            assert_eq!(
+                r.resolve("./cjs.cjs", s).unwrap().matched_by,
+                Some(MatchedBy::Extension(Extensions::Cts))
+            );
+            // `.cjs` is not `.js`, nor is `.mjs`
+            assert!(r.resolve("./index.cjs", s).is_err());
+            assert!(r.resolve("./index.mjs", s).is_err());
+            // Declarations are not wanted by default
+            assert!(r.resolve("./typed.js", s).is_err());
+        });
+    }
+
+    #[test]
+    fn declarations() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Node, |options| {
+                options.extension_alias = EsResolveOptions::typescript_extension_alias(true);
+            });
+            let s = Path::new("/app/src/index.ts");
+
+            assert_eq!(
+                r.resolve("./index.js", s).unwrap().path,
+                Path::new("/app/src/index.ts")
+            );
+            assert_eq!(
+                r.resolve("./typed.js", s).unwrap().path,
+                Path::new("/app/src/typed.d.ts")
+            );
+            assert_eq!(
+                r.resolve("./typed.mjs", s).unwrap().path,
+                Path::new("/app/src/typed.d.mts")
+            );
+            assert_eq!(
+                r.resolve("./config.cjs", s).unwrap().path,
+                Path::new("/app/src/config.d.cts")
+            );
+        });
+    }
+
+    #[test]
+    fn custom_extension_alias() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Node, |options| {
+                options.extension_alias =
+                    vec![(Extensions::Json, vec![Extensions::Mts, Extensions::Ts])];
+            });
+            let s = Path::new("/app/src/index.ts");
+
+            assert_eq!(
+                r.resolve("./index.json", s).unwrap().path,
+                Path::new("/app/src/index.ts")
+            );
+            assert_eq!(
+                r.resolve("./esm.json", s).unwrap().path,
+                Path::new("/app/src/esm.mts")
+            );
+            assert!(r.resolve("./index.js", s).is_err());
+
+            let r = resolver(TargetEnv::Node, |options| options.extension_alias.clear());
+
+            assert!(r.resolve("./index.js", s).is_err());
+            assert!(r.resolve("./cjs.cjs", s).is_err());
+        });
+    }
+
+    #[test]
+    fn custom_extensions() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Browser, |options| {
+                options.extensions = ["vue", "svelte", ".module.css", "d.ts", ".ts"]
+                    .into_iter()
+                    .map(|ext| Extensions::from(ext).unwrap())
+                    .collect();
+                options.extension_alias =
+                    vec![(Extensions::Css, vec![Extensions::from("scss").unwrap()])];
+            });
+            let s = Path::new("/app/src/index.ts");
+
+            assert_eq!(
+                r.resolve("./App", s).unwrap().path,
+                Path::new("/app/src/App.vue")
+            );
+            assert_eq!(
+                r.resolve("./App", s).unwrap().matched_by,
+                Some(MatchedBy::Extension(Extensions::Custom(String::from(
+                    "vue"
+                ))))
+            );
+            assert_eq!(
+                r.resolve("./Button", s).unwrap().path,
+                Path::new("/app/src/Button.svelte")
+            );
+            assert_eq!(
+                r.resolve("./button", s).unwrap().path,
+                Path::new("/app/src/button.module.css")
+            );
+            assert_eq!(
+                r.resolve("./legacy", s).unwrap().path,
+                Path::new("/app/src/legacy.d.ts")
+            );
+            assert_eq!(
+                r.resolve("./style.css", s).unwrap().path,
+                Path::new("/app/src/style.scss")
+            );
+        });
+    }
+
+    #[test]
+    fn main_files() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/index.ts");
+
+            let r = resolver(TargetEnv::Browser, |_| {});
+            assert_eq!(
+                r.resolve("./widget", s).unwrap().path,
+                Path::new("/app/src/widget/index.ts")
+            );
+            assert!(r.resolve("./deno", s).is_err());
+
+            let r = resolver(TargetEnv::Browser, |options| {
+                options.main_files = vec![format!("index.web"), format!("index"), format!("mod")];
+            });
+
+            let resolution = r.resolve("./widget", s).unwrap();
+            assert_eq!(resolution.path, Path::new("/app/src/widget/index.web.tsx"));
+            assert_eq!(resolution.matched_by, Some(MatchedBy::IndexFile));
+            assert_eq!(
+                r.resolve("./deno/", s).unwrap().path,
+                Path::new("/app/src/deno/mod.ts")
+            );
+        });
+    }
+
+    #[test]
+    fn extensions_round_trip() {
+        for ext in ["js", "mts", "d.ts", "d.cts", "vue", "module.css", "json5"] {
+            assert_eq!(Extensions::from(ext).unwrap().to_str(), ext);
+            assert_eq!(
+                Extensions::from(&format!(".{}", ext)),
+                Extensions::from(ext)
+            );
+        }
+
+        assert_eq!(Extensions::from("ts"), Some(Extensions::Ts));
+        assert_eq!(Extensions::from("d.ts"), Some(Extensions::Dts));
+        assert_eq!(
+            Extensions::from("wasm"),
+            Some(Extensions::Custom(String::from("wasm")))
+        );
+        assert_eq!(Extensions::from(""), None);
+        assert_eq!(Extensions::from("."), None);
+        assert_eq!(Extensions::from("a/b"), None);
+    }
+}
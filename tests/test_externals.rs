@@ -0,0 +1,175 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+    use regex::Regex;
+
+    fn resolver(externals: Vec<Externals>, verify_externals: bool) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(
+            TargetEnv::Browser,
+            MemoryFileSystem::from_json(
+                r##"
+                {
+                    "/app": {
+                        "package.json": {
+                            "dependencies": { "lodash": "^4.17.21" },
+                            "peerDependencies": { "react": "^18.0.0" },
+                            "devDependencies": { "typescript": "^5.0.0" }
+                        },
+                        "src/index.js": "",
+                        "src/utils.js": "",
+                        "node_modules/react": {
+                            "package.json": { "name": "react", "version": "18.2.0" },
+                            "index.js": "",
+                            "jsx-runtime.js": ""
+                        },
+                        "node_modules/lodash/index.js": "",
+                        "node_modules/typescript/index.js": "",
+                        "node_modules/@babel/runtime/index.js": ""
+                    }
+                }
+                "##,
+            )
+            .unwrap(),
+            |options| {
+                options.externals = externals;
+                options.verify_externals = verify_externals;
+            },
+        )
+    }
+
+    fn external(resolution: &Resolution) -> Option<&str> {
+        match &resolution.kind {
+            ResolutionKind::External { package } => Some(package),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn externals() {
+        with_tracing(|| {
+            let r = resolver(
+                vec![
+                    Externals::Exact(String::from("react")),
+                    Externals::Prefix(String::from("@babel/")),
+                    Externals::Regex(Regex::new(r"^lodash(/.*)?$").unwrap()),
+                ],
+                false,
+            );
+            let s = Path::new("/app/src/index.js");
+
+            let resolution = r.resolve("react", s).unwrap();
+            assert_eq!(external(&resolution), Some("react"));
+            assert_eq!(resolution.path, Path::new("react"));
+            assert_eq!(resolution.package, None);
+
+            assert_eq!(
+                r.resolve("react/jsx-runtime", s).unwrap().path,
+                Path::new("/app/node_modules/react/jsx-runtime.js")
+            );
+            assert_eq!(
+                external(&r.resolve("@babel/runtime/helpers/extends", s).unwrap()),
+                Some("@babel/runtime")
+            );
+            assert_eq!(
+                external(&r.resolve("lodash/get", s).unwrap()),
+                Some("lodash")
+            );
+            assert!(matches!(
+                r.resolve("lodash-es", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+            // Not verified
+            assert_eq!(
+                external(&r.resolve("@babel/core", s).unwrap()),
+                Some("@babel/core")
+            );
+        });
+    }
+
+    #[test]
+    fn builtins() {
+        with_tracing(|| {
+            let r = resolver(vec![Externals::Regex(Regex::new(".*").unwrap())], false);
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                external(&r.resolve("left-pad", s).unwrap()),
+                Some("left-pad")
+            );
+            for specifier in ["fs", "node:fs", "node:test"] {
+                assert!(matches!(
+                    r.resolve(specifier, s),
+                    Err(EsResolverError::NodeBuiltinInBrowser { .. })
+                ));
+            }
+
+            let r = test_util::resolver(
+                TargetEnv::Node,
+                MemoryFileSystem::from_files([("/app/src/index.js", "")]),
+                |options| options.externals = vec![Externals::Prefix(String::from("f"))],
+            );
+            let resolution = r.resolve("fs/promises", s).unwrap();
+            assert_eq!(resolution.kind, ResolutionKind::NodeBuiltin);
+            assert_eq!(resolution.path, Path::new("node:fs/promises"));
+            assert_eq!(external(&r.resolve("fastify", s).unwrap()), Some("fastify"));
+        });
+    }
+
+    #[test]
+    fn dependencies() {
+        with_tracing(|| {
+            let r = resolver(vec![Externals::Dependencies], false);
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(external(&r.resolve("react", s).unwrap()), Some("react"));
+            assert_eq!(
+                external(&r.resolve("react/jsx-runtime", s).unwrap()),
+                Some("react")
+            );
+            assert_eq!(external(&r.resolve("lodash", s).unwrap()), Some("lodash"));
+            // Only `dependencies` and `peerDependencies`
+            assert_eq!(
+                r.resolve("typescript", s).unwrap().path,
+                Path::new("/app/node_modules/typescript/index.js")
+            );
+            assert_eq!(
+                r.resolve("./utils", s).unwrap().path,
+                Path::new("/app/src/utils.js")
+            );
+        });
+    }
+
+    #[test]
+    fn verify_externals() {
+        with_tracing(|| {
+            let r = resolver(
+                vec![
+                    Externals::Exact(String::from("react")),
+                    Externals::Exact(String::from("preact")),
+                ],
+                true,
+            );
+            let s = Path::new("/app/src/index.js");
+
+            let resolution = r.resolve("react", s).unwrap();
+            assert_eq!(external(&resolution), Some("react"));
+            assert_eq!(
+                resolution.package,
+                Some(PackageInfo {
+                    package_json: Path::new("/app/node_modules/react/package.json").into(),
+                    name: Some(String::from("react")),
+                    version: Some(String::from("18.2.0")),
+                })
+            );
+            assert!(matches!(
+                r.resolve("preact", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+        });
+    }
+}
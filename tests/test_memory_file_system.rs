@@ -0,0 +1,156 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver(env: TargetEnv, fs: MemoryFileSystem) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(env, fs, |_| {})
+    }
+
+    #[test]
+    fn node_modules() {
+        with_tracing(|| {
+            let fs = MemoryFileSystem::from_json(
+                r#"
+                {
+                    "/app": {
+                        "src/index.ts": "",
+                        "src/utils.ts": "",
+                        "tsconfig.json": {
+                            "compilerOptions": { "baseUrl": ".", "paths": { "@/*": ["src/*"] } }
+                        },
+                        "node_modules": {
+                            "exports": {
+                                "package.json": {
+                                    "exports": {
+                                        ".": { "import": "./index.mjs", "require": "./index.cjs" }
+                                    }
+                                },
+                                "index.mjs": "",
+                                "index.cjs": ""
+                            },
+                            "linked": { "$symlink": "../packages/linked" }
+                        },
+                        "packages/linked": {
+                            "package.json": { "main": "./main.js" },
+                            "main.js": ""
+                        }
+                    }
+                }
+                "#,
+            )
+            .unwrap();
+
+            let r = resolver(TargetEnv::Browser, fs);
+            let s = Path::new("/app/src/index.ts");
+
+            assert_eq!(
+                r.resolve("./utils", s).unwrap().path,
+                Path::new("/app/src/utils.ts")
+            );
+            assert_eq!(
+                r.resolve("@/utils", s).unwrap().path,
+                Path::new("/app/src/utils.ts")
+            );
+            assert_eq!(
+                r.resolve("exports", s).unwrap().path,
+                Path::new("/app/node_modules/exports/index.mjs")
+            );
+            assert_eq!(
+                r.resolve("linked", s).unwrap().path,
+                Path::new("/app/node_modules/linked/main.js")
+            );
+            assert!(r.resolve("missing", s).is_err());
+        });
+    }
+
+    #[test]
+    fn from_files() {
+        let fs = MemoryFileSystem::from_files([
+            ("/app/index.js", ""),
+            ("/app/lib/index.js", ""),
+            ("app/lib/../other.js", ""),
+        ]);
+
+        assert!(fs.contains("/app/other.js"));
+
+        let r = resolver(TargetEnv::Node, fs);
+        let s = Path::new("/app/index.js");
+
+        assert_eq!(r.resolve("./lib", s).unwrap().path, Path::new("/app/lib/index.js"));
+        assert_eq!(r.resolve("./other", s).unwrap().path, Path::new("/app/other.js"));
+
+        // The resolver caches file lookups until told otherwise
+        assert!(r.resolve("./new", s).is_err());
+        r.file_system().add_file("/app/new.js", "");
+        assert!(r.resolve("./new", s).is_err());
+        r.clear_cache();
+        assert_eq!(r.resolve("./new", s).unwrap().path, Path::new("/app/new.js"));
+    }
+
+    #[test]
+    fn symlinks() {
+        let fs = MemoryFileSystem::new();
+        fs.add_file("/real/file.js", "content");
+        fs.add_symlink("/link", "real");
+        fs.add_symlink("/real/self", "../real/file.js");
+        fs.add_symlink("/loop/a", "b");
+        fs.add_symlink("/loop/b", "a");
+
+        assert_eq!(fs.read_to_string(Path::new("/link/file.js")).unwrap(), "content");
+        assert_eq!(
+            fs.canonicalize(Path::new("/link/self")).unwrap(),
+            PathBuf::from("/real/file.js")
+        );
+        assert_eq!(fs.read_link(Path::new("/link")).unwrap(), PathBuf::from("real"));
+        assert!(fs.read_link(Path::new("/link/file.js")).is_err());
+
+        let metadata = fs.metadata(Path::new("/link")).unwrap();
+        assert!(metadata.is_dir && metadata.is_symlink);
+        let metadata = fs.metadata(Path::new("/link/file.js")).unwrap();
+        assert!(metadata.is_file && !metadata.is_symlink);
+
+        assert_eq!(
+            fs.read_dir(Path::new("/link")).unwrap(),
+            vec![PathBuf::from("/link/file.js"), PathBuf::from("/link/self")]
+        );
+        assert!(fs.read_dir(Path::new("/link/file.js")).is_err());
+
+        assert!(fs.metadata(Path::new("/loop/a")).is_err());
+        assert!(fs.metadata(Path::new("/real/missing.js")).is_err());
+
+        fs.remove("/real");
+        assert!(!fs.is_file(Path::new("/link/file.js")));
+    }
+
+    #[test]
+    fn invalid_description() {
+        assert!(MemoryFileSystem::from_json(r#"{ "/a.js": 1 }"#).is_err());
+        assert!(MemoryFileSystem::from_json(r#"{ "/a.js": "" "#).is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn from_yaml() {
+        let fs = MemoryFileSystem::from_yaml(
+            r#"
+            /app:
+              index.js: ""
+              node_modules/lib:
+                package.json: { "main": "./main.js" }
+                main.js: ""
+            "#,
+        )
+        .unwrap();
+
+        let r = resolver(TargetEnv::Node, fs);
+        assert_eq!(
+            r.resolve("lib", Path::new("/app/index.js")).unwrap().path,
+            Path::new("/app/node_modules/lib/main.js")
+        );
+    }
+}
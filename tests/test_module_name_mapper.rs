@@ -0,0 +1,135 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+    use regex::Regex;
+
+    fn resolver(
+        root_dir: Option<&str>,
+        module_name_mapper: Vec<(&str, Vec<&str>)>,
+    ) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(
+            TargetEnv::Node,
+            MemoryFileSystem::from_json(
+                r##"
+                {
+                    "/app": {
+                        "package.json": {},
+                        "src/index.js": "",
+                        "src/components/button.tsx": "",
+                        "src/styles.css": "",
+                        "src/__mocks__/file.js": "",
+                        "lib/legacy/button.js": "",
+                        "node_modules/identity-obj-proxy/index.js": ""
+                    }
+                }
+                "##,
+            )
+            .unwrap(),
+            |options| {
+                options.root_dir = root_dir.map(PathBuf::from);
+                options.module_name_mapper = module_name_mapper
+                    .into_iter()
+                    .map(|(pattern, targets)| {
+                        (
+                            Regex::new(pattern).unwrap(),
+                            targets.into_iter().map(String::from).collect(),
+                        )
+                    })
+                    .collect();
+            },
+        )
+    }
+
+    #[test]
+    fn jest() {
+        with_tracing(|| {
+            let r = resolver(
+                None,
+                vec![
+                    (r"^@/(.*)$", vec!["<rootDir>/src/$1"]),
+                    (r"\.(css|less)$", vec!["identity-obj-proxy"]),
+                    (r"\.(png|svg)$", vec!["<rootDir>/src/__mocks__/file.js"]),
+                ],
+            );
+            let s = Path::new("/app/src/index.js");
+
+            let resolution = r.resolve("@/components/button", s).unwrap();
+            assert_eq!(resolution.path, Path::new("/app/src/components/button.tsx"));
+            assert_eq!(
+                resolution.matched_by,
+                Some(MatchedBy::ModuleNameMapper(String::from(r"^@/(.*)$")))
+            );
+            // Relative specifiers are mapped too
+            assert_eq!(
+                r.resolve("./styles.css", s).unwrap().path,
+                Path::new("/app/node_modules/identity-obj-proxy/index.js")
+            );
+            assert_eq!(
+                r.resolve("../assets/logo.svg", s).unwrap().path,
+                Path::new("/app/src/__mocks__/file.js")
+            );
+
+            let (_, trace) = r.resolve_with_trace("@/components/button", s).unwrap();
+            assert!(trace.steps.contains(&TraceStep::ModuleNameMapper {
+                pattern: String::from(r"^@/(.*)$"),
+                target: String::from("/app/src/components/button"),
+            }));
+        });
+    }
+
+    #[test]
+    fn root_dir_and_fallbacks() {
+        with_tracing(|| {
+            let r = resolver(
+                Some("/app/lib"),
+                vec![
+                    (
+                        r"^(ui|components)/(.*)$",
+                        vec!["<rootDir>/$1/$2", "<rootDir>/legacy/$2$9"],
+                    ),
+                    (r"^missing$", vec!["<rootDir>/missing"]),
+                    (r"^missing", vec!["identity-obj-proxy"]),
+                ],
+            );
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve("ui/button", s).unwrap().path,
+                Path::new("/app/lib/legacy/button.js")
+            );
+            // The first pattern that matches decides
+            assert!(matches!(
+                r.resolve("missing", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+            assert_eq!(
+                r.resolve("missing-pkg", s).unwrap().path,
+                Path::new("/app/node_modules/identity-obj-proxy/index.js")
+            );
+        });
+    }
+
+    #[test]
+    fn not_mapped_again() {
+        with_tracing(|| {
+            let r = resolver(
+                None,
+                vec![
+                    (r"^(.*)\.css$", vec!["$1.tsx"]),
+                    (r"^(.*)\.tsx$", vec!["$1.css"]),
+                ],
+            );
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve("./components/button.css", s).unwrap().path,
+                Path::new("/app/src/components/button.tsx")
+            );
+        });
+    }
+}
@@ -0,0 +1,131 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver(configure: impl FnOnce(&mut EsResolveOptions)) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(
+            TargetEnv::Browser,
+            MemoryFileSystem::from_files([
+                ("/app/src/pages/index.js", ""),
+                ("/app/src/components/button.js", ""),
+                ("/app/node_modules/react/index.js", ""),
+                ("/app/web_modules/preact/index.js", ""),
+                ("/app/node_modules/preact/index.js", ""),
+                ("/global/lib/left-pad/index.js", ""),
+            ]),
+            configure,
+        )
+    }
+
+    #[test]
+    fn lookup_paths() {
+        let r = resolver(|_| {});
+
+        assert_eq!(
+            r.lookup_paths(Path::new("/app/node_modules/pkg/lib/index.js")),
+            [
+                "/app/node_modules/pkg/lib/node_modules",
+                "/app/node_modules/pkg/node_modules",
+                "/app/node_modules",
+                "/node_modules",
+            ]
+            .map(PathBuf::from)
+        );
+
+        let r = resolver(|options| {
+            options.modules = vec![
+                format!("web_modules"),
+                format!("/app/src"),
+                format!("node_modules"),
+            ];
+        });
+
+        assert_eq!(
+            r.lookup_paths(Path::new("/app/src/index.js")),
+            [
+                "/app/src/web_modules",
+                "/app/src/node_modules",
+                "/app/web_modules",
+                "/app/node_modules",
+                "/web_modules",
+                "/node_modules",
+                "/app/src",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn modules() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/pages/index.js");
+
+            let r = resolver(|_| {});
+            assert_eq!(
+                r.resolve("preact", s).unwrap().path,
+                Path::new("/app/node_modules/preact/index.js")
+            );
+            assert!(r.resolve("components/button", s).is_err());
+
+            let r = resolver(|options| {
+                options.modules = vec![
+                    format!("web_modules"),
+                    format!("node_modules"),
+                    format!("/app/src"),
+                ];
+            });
+
+            assert_eq!(
+                r.resolve("preact", s).unwrap().path,
+                Path::new("/app/web_modules/preact/index.js")
+            );
+            assert_eq!(
+                r.resolve("react", s).unwrap().path,
+                Path::new("/app/node_modules/react/index.js")
+            );
+            assert_eq!(
+                r.resolve("components/button", s).unwrap().path,
+                Path::new("/app/src/components/button.js")
+            );
+        });
+    }
+
+    #[test]
+    fn global_folders() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/pages/index.js");
+
+            let r = resolver(|_| {});
+            assert!(r.resolve("left-pad", s).is_err());
+
+            let r = resolver(|options| {
+                options.global_folders = [
+                    "/global/lib",
+                    "/home/user/.node_modules",
+                    "/home/user/.node_libraries",
+                ]
+                .map(PathBuf::from)
+                .to_vec();
+            });
+
+            assert_eq!(
+                r.lookup_paths(s).split_off(4),
+                [
+                    "/global/lib",
+                    "/home/user/.node_modules",
+                    "/home/user/.node_libraries",
+                ]
+                .map(PathBuf::from)
+            );
+            assert_eq!(
+                r.resolve("left-pad", s).unwrap().path,
+                Path::new("/global/lib/left-pad/index.js")
+            );
+        });
+    }
+}
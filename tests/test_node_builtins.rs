@@ -0,0 +1,132 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver(env: TargetEnv, node_version: Option<NodeVersion>) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(
+            env,
+            MemoryFileSystem::from_files([
+                ("/app/src/index.js", ""),
+                ("/app/node_modules/test/index.js", ""),
+                ("/app/node_modules/test/reporters.js", ""),
+            ]),
+            |options| {
+                options.node_version = node_version;
+            },
+        )
+    }
+
+    fn builtin(r: &Resolver<MemoryFileSystem>, specifier: &str) -> Option<String> {
+        match r.resolve(specifier, Path::new("/app/src/index.js")) {
+            Ok(resolution) if resolution.kind == ResolutionKind::NodeBuiltin => {
+                Some(resolution.path.to_string_lossy().into())
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn prefix_only() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Node, None);
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(builtin(&r, "fs"), Some(String::from("node:fs")));
+            assert_eq!(builtin(&r, "node:fs"), Some(String::from("node:fs")));
+            assert_eq!(builtin(&r, "node:test"), Some(String::from("node:test")));
+            assert_eq!(
+                builtin(&r, "node:test/reporters"),
+                Some(String::from("node:test/reporters"))
+            );
+            assert_eq!(
+                builtin(&r, "node:sqlite"),
+                Some(String::from("node:sqlite"))
+            );
+            assert_eq!(builtin(&r, "node:sea"), Some(String::from("node:sea")));
+
+            // Without the prefix, they are packages
+            assert_eq!(
+                r.resolve("test", s).unwrap().path,
+                Path::new("/app/node_modules/test/index.js")
+            );
+            assert_eq!(
+                r.resolve("test/reporters", s).unwrap().path,
+                Path::new("/app/node_modules/test/reporters.js")
+            );
+            assert!(matches!(
+                r.resolve("sqlite", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn unknown() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/index.js");
+
+            for env in [TargetEnv::Node, TargetEnv::Browser] {
+                let r = resolver(env, None);
+                let e = r.resolve("node:nope", s).unwrap_err();
+
+                assert!(matches!(
+                    &e,
+                    EsResolverError::UnknownBuiltinModule(specifier) if specifier == "node:nope"
+                ));
+                assert_eq!(e.code(), "ERR_UNKNOWN_BUILTIN_MODULE");
+                assert_eq!(e.to_string(), "No such built-in module: node:nope");
+            }
+
+            let r = resolver(TargetEnv::Browser, None);
+            assert!(matches!(
+                r.resolve("node:test", s),
+                Err(EsResolverError::NodeBuiltinInBrowser { .. })
+            ));
+            assert_eq!(
+                r.resolve("test", s).unwrap().path,
+                Path::new("/app/node_modules/test/index.js")
+            );
+        });
+    }
+
+    #[test]
+    fn node_version() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/index.js");
+
+            let r = resolver(TargetEnv::Node, Some((16, 20, 0)));
+            assert_eq!(builtin(&r, "fs"), Some(String::from("node:fs")));
+            assert_eq!(
+                builtin(&r, "stream/web"),
+                Some(String::from("node:stream/web"))
+            );
+            assert_eq!(builtin(&r, "readline/promises"), None);
+            assert!(matches!(
+                r.resolve("node:test", s),
+                Err(EsResolverError::UnknownBuiltinModule(_))
+            ));
+            assert!(matches!(
+                r.resolve("node:readline/promises", s),
+                Err(EsResolverError::UnknownBuiltinModule(_))
+            ));
+
+            let r = resolver(TargetEnv::Node, Some((22, 5, 0)));
+            assert_eq!(
+                builtin(&r, "node:sqlite"),
+                Some(String::from("node:sqlite"))
+            );
+            assert_eq!(
+                builtin(&r, "node:test/reporters"),
+                Some(String::from("node:test/reporters"))
+            );
+
+            let r = resolver(TargetEnv::Node, Some((22, 4, 1)));
+            assert_eq!(builtin(&r, "node:sqlite"), None);
+        });
+    }
+}
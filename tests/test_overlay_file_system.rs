@@ -0,0 +1,84 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn source(s: &str) -> PathBuf {
+        PathBuf::from("tests")
+            .join("fixtures")
+            .join(s)
+            .canonicalize()
+            .unwrap()
+    }
+
+    fn resolver() -> Resolver<OverlayFileSystem> {
+        test_util::resolver(TargetEnv::Browser, OverlayFileSystem::default(), |_| {})
+    }
+
+    #[test]
+    fn unsaved_files() {
+        with_tracing(|| {
+            let r = resolver();
+            let s = source("relative/index.js");
+            let button = source("relative").join("Button.tsx");
+
+            assert!(r.resolve("./Button", &s).is_err());
+
+            // A file created in the editor
+            r.file_system().add_file(&button, "export default 1");
+            r.clear_cache();
+            assert_eq!(r.resolve("./Button", &s).unwrap().path, button);
+
+            // Resolving from an unsaved file
+            assert_eq!(
+                r.resolve("./ts", &button).unwrap().path,
+                source("relative/ts.ts")
+            );
+
+            // A file deleted in the editor
+            r.file_system().shadow_file(source("relative/ts.ts"));
+            r.clear_cache();
+            assert!(r.resolve("./ts", &s).is_err());
+
+            let listed = r.file_system().read_dir(&source("relative")).unwrap();
+            assert!(listed.contains(&button));
+            assert!(!listed.contains(&source("relative/ts.ts")));
+            assert!(listed.contains(&source("relative/js.js")));
+
+            // Closing both buffers without saving
+            r.file_system().remove_file(&button);
+            r.file_system().remove_file(source("relative/ts.ts"));
+            r.clear_cache();
+            assert!(r.resolve("./Button", &s).is_err());
+            assert_eq!(r.resolve("./ts", &s).unwrap().path, source("relative/ts.ts"));
+        });
+    }
+
+    #[test]
+    fn edited_tsconfig() {
+        with_tracing(|| {
+            let r = resolver();
+            let s = source("tspaths/constant/index.ts");
+
+            assert_eq!(
+                r.resolve("constant", &s).unwrap().path,
+                source("tspaths/constant/constant.ts")
+            );
+
+            r.file_system().add_file(
+                source("tspaths/constant/tsconfig.json"),
+                r#"{ "compilerOptions": { "baseUrl": ".", "paths": { "@c": ["./constant"] } } }"#,
+            );
+            r.clear_cache();
+
+            assert_eq!(
+                r.resolve("@c", &s).unwrap().path,
+                source("tspaths/constant/constant.ts")
+            );
+        });
+    }
+}
@@ -0,0 +1,476 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver(env: TargetEnv, json: &str) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(env, MemoryFileSystem::from_json(json).unwrap(), |_| {})
+    }
+
+    #[test]
+    fn imports() {
+        with_tracing(|| {
+            let r = resolver(
+                TargetEnv::Node,
+                r##"
+                {
+                    "/app": {
+                        "package.json": {
+                            "imports": {
+                                "#utils": "./src/utils/index.js",
+                                "#utils/*": "./src/utils/*.js",
+                                "#utils/internal/*": null,
+                                "#dep": { "node": "dep-node-native", "default": "./src/dep.js" },
+                                "#lib/*": "lib/*",
+                                "#uninstalled": "not-installed"
+                            }
+                        },
+                        "src/index.js": "",
+                        "src/dep.js": "",
+                        "src/utils/index.js": "",
+                        "src/utils/format.js": "",
+                        "node_modules/dep-node-native/index.js": "",
+                        "node_modules/lib/sub.js": ""
+                    }
+                }
+                "##,
+            );
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve("#utils", s).unwrap().path,
+                Path::new("/app/src/utils/index.js")
+            );
+            assert_eq!(
+                r.resolve("#utils/format", s).unwrap().path,
+                Path::new("/app/src/utils/format.js")
+            );
+            assert_eq!(
+                r.resolve("#dep", s).unwrap().path,
+                Path::new("/app/node_modules/dep-node-native/index.js")
+            );
+            assert_eq!(
+                r.resolve("#lib/sub", s).unwrap().path,
+                Path::new("/app/node_modules/lib/sub.js")
+            );
+            assert!(matches!(
+                r.resolve("#missing", s),
+                Err(EsResolverError::PackageImportNotDefined(_))
+            ));
+            // Defined, but the package it maps to is not installed
+            let e = r.resolve("#uninstalled", s).unwrap_err();
+            assert_eq!(e.code(), "ERR_MODULE_NOT_FOUND");
+            assert!(e.to_string().contains("\"not-installed\""));
+            assert!(matches!(
+                r.resolve("#/utils", s),
+                Err(EsResolverError::InvalidModuleSpecifier(_))
+            ));
+        });
+
+        with_tracing(|| {
+            let r = resolver(
+                TargetEnv::Browser,
+                r##"
+                {
+                    "/app": {
+                        "package.json": {
+                            "imports": {
+                                "#dep": { "node": "dep-node-native", "default": "./src/dep.js" }
+                            }
+                        },
+                        "src/index.js": "",
+                        "src/dep.js": ""
+                    }
+                }
+                "##,
+            );
+
+            assert_eq!(
+                r.resolve("#dep", Path::new("/app/src/index.js"))
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/dep.js")
+            );
+        });
+    }
+
+    static EXPORTS: &str = r##"
+    {
+        "/app": {
+            "index.js": "",
+            "node_modules/pkg": {
+                "package.json": {
+                    "main": "./main.js",
+                    "exports": {
+                        ".": "./main.js",
+                        "./feature": { "import": "./feature.mjs" },
+                        "./internal/*": null,
+                        "./*": "./lib/*.js"
+                    }
+                },
+                "main.js": "",
+                "feature.mjs": "",
+                "internal/secret.js": "",
+                "lib/public.js": "",
+                "unlisted.js": ""
+            }
+        }
+    }
+    "##;
+
+    #[test]
+    fn exports_encapsulation() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Node, EXPORTS);
+            let s = Path::new("/app/index.js");
+
+            assert_eq!(
+                r.resolve("pkg", s).unwrap().path,
+                Path::new("/app/node_modules/pkg/main.js")
+            );
+            assert_eq!(
+                r.resolve("pkg/public", s).unwrap().path,
+                Path::new("/app/node_modules/pkg/lib/public.js")
+            );
+
+            // Not exported under the `require` condition
+            match r.resolve("pkg/feature", s) {
+                Err(EsResolverError::PackagePathNotExported {
+                    package, subpath, ..
+                }) => {
+                    assert_eq!(package, "pkg");
+                    assert_eq!(subpath, "./feature");
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+
+            // Excluded with `null`
+            assert!(matches!(
+                r.resolve("pkg/internal/secret.js", s),
+                Err(EsResolverError::PackagePathNotExported { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn exports_non_strict() {
+        with_tracing(|| {
+            let r = test_util::resolver(
+                TargetEnv::Node,
+                MemoryFileSystem::from_json(EXPORTS).unwrap(),
+                |options| options.strict_exports = false,
+            );
+            let s = Path::new("/app/index.js");
+
+            assert_eq!(
+                r.resolve("pkg/internal/secret.js", s).unwrap().path,
+                Path::new("/app/node_modules/pkg/internal/secret.js")
+            );
+        });
+    }
+
+    #[test]
+    fn exports_target_validation() {
+        with_tracing(|| {
+            let r = resolver(
+                TargetEnv::Node,
+                r##"
+                {
+                    "/app": {
+                        "index.js": "",
+                        "node_modules/evil": {
+                            "package.json": {
+                                "exports": {
+                                    "./passwd": "../../etc/passwd",
+                                    "./bare": "node_modules/x",
+                                    "./nested": "./lib/../../other/index.js",
+                                    "./encoded": "./%2e%2e/other/index.js",
+                                    "./deps/*": "./node_modules/*.js",
+                                    "./lib/*": "./lib/*.js",
+                                    "./fallback": ["../outside.js", "./lib/ok.js"]
+                                }
+                            },
+                            "lib/ok.js": ""
+                        }
+                    }
+                }
+                "##,
+            );
+            let s = Path::new("/app/index.js");
+
+            for specifier in [
+                "evil/passwd",
+                "evil/bare",
+                "evil/nested",
+                "evil/encoded",
+                "evil/deps/x",
+                "evil/lib/../../../etc/passwd",
+                "evil/lib/node_modules/x",
+            ] {
+                match r.resolve(specifier, s) {
+                    Err(EsResolverError::InvalidPackageTarget { package_json, .. }) => {
+                        assert_eq!(
+                            package_json,
+                            Path::new("/app/node_modules/evil/package.json")
+                        );
+                    }
+                    other => panic!("{} resolves to {:?}", specifier, other),
+                }
+            }
+
+            assert_eq!(
+                r.resolve("evil/lib/ok", s).unwrap().path,
+                Path::new("/app/node_modules/evil/lib/ok.js")
+            );
+
+            // Invalid targets in an array are skipped
+            assert_eq!(
+                r.resolve("evil/fallback", s).unwrap().path,
+                Path::new("/app/node_modules/evil/lib/ok.js")
+            );
+        });
+    }
+
+    static BROWSER: &str = r##"
+    {
+        "/app": {
+            "package.json": {
+                "browser": {
+                    "./src/server.js": "./src/client.js",
+                    "./src/secret": false,
+                    "fs": false,
+                    "http": "stream-http",
+                    "native": "./src/native-shim.js"
+                }
+            },
+            "src/index.js": "",
+            "src/server.js": "",
+            "src/client.js": "",
+            "src/secret.js": "",
+            "src/native-shim.js": "",
+            "node_modules/stream-http/index.js": "",
+            "node_modules/native/index.js": "",
+            "node_modules/pkg": {
+                "package.json": {
+                    "main": "./lib/node.js",
+                    "browser": {
+                        "./lib/node.js": "./lib/browser.js",
+                        "./lib/util": "./lib/util-browser.js",
+                        "./lib/debug": false
+                    }
+                },
+                "lib/node.js": "",
+                "lib/browser.js": "",
+                "lib/util/index.js": "",
+                "lib/util-browser.js": "",
+                "lib/debug.js": ""
+            }
+        }
+    }
+    "##;
+
+    #[test]
+    fn browser_field() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Browser, BROWSER);
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve("./server", s).unwrap().path,
+                Path::new("/app/src/client.js")
+            );
+            let ignored = r.resolve("./secret.js", s).unwrap();
+            assert_eq!(ignored.kind, ResolutionKind::Ignored);
+            assert_eq!(ignored.path, Path::new("/app/src/secret.js"));
+
+            let ignored = r.resolve("fs", s).unwrap();
+            assert_eq!(ignored.kind, ResolutionKind::Ignored);
+            assert_eq!(ignored.path, Path::new("fs"));
+            assert_eq!(
+                r.resolve("http", s).unwrap().path,
+                Path::new("/app/node_modules/stream-http/index.js")
+            );
+            assert_eq!(
+                r.resolve("native", s).unwrap().path,
+                Path::new("/app/src/native-shim.js")
+            );
+
+            // Files inside a dependency are remapped by its own package.json
+            assert_eq!(
+                r.resolve("pkg", s).unwrap().path,
+                Path::new("/app/node_modules/pkg/lib/browser.js")
+            );
+            assert_eq!(
+                r.resolve("pkg/lib/util", s).unwrap().path,
+                Path::new("/app/node_modules/pkg/lib/util-browser.js")
+            );
+            assert_eq!(
+                r.resolve("pkg/lib/debug", s).unwrap().kind,
+                ResolutionKind::Ignored
+            );
+        });
+
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Node, BROWSER);
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve("./server", s).unwrap().path,
+                Path::new("/app/src/server.js")
+            );
+            assert_eq!(r.resolve("fs", s).unwrap().path, Path::new("node:fs"));
+            assert_eq!(
+                r.resolve("pkg", s).unwrap().path,
+                Path::new("/app/node_modules/pkg/lib/node.js")
+            );
+        });
+    }
+
+    #[test]
+    fn custom_main_fields() {
+        with_tracing(|| {
+            let fs = MemoryFileSystem::from_json(
+                r##"
+                {
+                    "/app": {
+                        "index.js": "",
+                        "node_modules/lib": {
+                            "package.json": {
+                                "main": "./dist/index.js",
+                                "source": "./src/index.ts",
+                                "jsnext:main": "./dist/index.mjs"
+                            },
+                            "dist/index.js": "",
+                            "dist/index.mjs": "",
+                            "src/index.ts": ""
+                        },
+                        "node_modules/styles": {
+                            "package.json": { "main": "./index.js", "style": "./theme.css" },
+                            "index.js": "",
+                            "theme.css": ""
+                        }
+                    }
+                }
+                "##,
+            )
+            .unwrap();
+
+            let r = test_util::resolver(TargetEnv::Browser, &fs, |options| {
+                options.main_fields = ["source", "style", "jsnext:main", "main"]
+                    .into_iter()
+                    .map(MainFields::from)
+                    .collect();
+            });
+            let s = Path::new("/app/index.js");
+
+            let resolution = r.resolve("lib", s).unwrap();
+            assert_eq!(
+                resolution.path,
+                Path::new("/app/node_modules/lib/src/index.ts")
+            );
+            assert_eq!(
+                resolution.matched_by,
+                Some(MatchedBy::MainField(MainFields::Custom(String::from(
+                    "source"
+                ))))
+            );
+            assert_eq!(
+                r.resolve("styles", s).unwrap().path,
+                Path::new("/app/node_modules/styles/theme.css")
+            );
+
+            let r = test_util::resolver(TargetEnv::Browser, fs, |options| {
+                options.main_fields = vec![MainFields::from("jsnext:main"), MainFields::Main];
+            });
+
+            assert_eq!(
+                r.resolve("lib", s).unwrap().path,
+                Path::new("/app/node_modules/lib/dist/index.mjs")
+            );
+            assert_eq!(
+                r.resolve("styles", s).unwrap().path,
+                Path::new("/app/node_modules/styles/index.js")
+            );
+        });
+    }
+
+    #[test]
+    fn non_string_main_fields() {
+        with_tracing(|| {
+            let r = resolver(
+                TargetEnv::Browser,
+                r##"
+                {
+                    "/app": {
+                        "index.js": "",
+                        "node_modules/axios": {
+                            "package.json": {
+                                "main": "./index.js",
+                                "module": false,
+                                "react-native": { "./lib/adapters/http.js": "./lib/adapters/xhr.js" }
+                            },
+                            "index.js": ""
+                        }
+                    }
+                }
+                "##,
+            );
+            let s = Path::new("/app/index.js");
+
+            let resolution = r.resolve("axios", s).unwrap();
+            assert_eq!(
+                resolution.path,
+                Path::new("/app/node_modules/axios/index.js")
+            );
+            assert_eq!(
+                resolution.matched_by,
+                Some(MatchedBy::MainField(MainFields::Main))
+            );
+        });
+    }
+
+    #[test]
+    fn invalid_browser_field() {
+        with_tracing(|| {
+            let json = r##"
+                {
+                    "/app": {
+                        "index.js": "",
+                        "node_modules/pkg": {
+                            "package.json": {
+                                "main": "./lib/node.js",
+                                "browser": {
+                                    "./x.js": null,
+                                    "./lib/node.js": "./lib/browser.js"
+                                }
+                            },
+                            "lib/node.js": "",
+                            "lib/browser.js": ""
+                        }
+                    }
+                }
+                "##;
+            let s = Path::new("/app/index.js");
+
+            assert_eq!(
+                resolver(TargetEnv::Node, json)
+                    .resolve("pkg", s)
+                    .unwrap()
+                    .path,
+                Path::new("/app/node_modules/pkg/lib/node.js")
+            );
+            // The valid entries still apply
+            assert_eq!(
+                resolver(TargetEnv::Browser, json)
+                    .resolve("pkg", s)
+                    .unwrap()
+                    .path,
+                Path::new("/app/node_modules/pkg/lib/browser.js")
+            );
+        });
+    }
+}
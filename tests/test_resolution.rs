@@ -0,0 +1,255 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver(env: TargetEnv) -> Resolver<MemoryFileSystem> {
+        let fs = MemoryFileSystem::from_json(
+            r##"
+            {
+                "/app": {
+                    "package.json": {
+                        "name": "app",
+                        "imports": { "#utils/*": "./src/utils/*.js" }
+                    },
+                    "tsconfig.json": {
+                        "compilerOptions": { "baseUrl": ".", "paths": { "@/*": ["src/*"] } }
+                    },
+                    "src/index.js": "",
+                    "src/logo.svg": "",
+                    "src/utils/format.js": "",
+                    "src/components/index.js": "",
+                    "node_modules/exports": {
+                        "package.json": {
+                            "name": "exports",
+                            "version": "1.2.3",
+                            "exports": { "./*": "./lib/*.js" }
+                        },
+                        "lib/feature.js": ""
+                    },
+                    "node_modules/main": {
+                        "package.json": { "name": "main", "version": "0.1.0", "main": "./main" },
+                        "main.js": ""
+                    },
+                    "node_modules/no-package-json/index.js": ""
+                }
+            }
+            "##,
+        )
+        .unwrap();
+
+        test_util::resolver(env, fs, |_| {})
+    }
+
+    #[test]
+    fn query_and_fragment() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Browser);
+            let s = Path::new("/app/src/index.js");
+
+            let resolution = r.resolve("./logo.svg?raw#top", s).unwrap();
+            assert_eq!(resolution.path, Path::new("/app/src/logo.svg"));
+            assert_eq!(resolution.query.as_deref(), Some("?raw"));
+            assert_eq!(resolution.fragment.as_deref(), Some("#top"));
+
+            let resolution = r.resolve("./logo.svg#top?not-a-query", s).unwrap();
+            assert_eq!(resolution.query, None);
+            assert_eq!(resolution.fragment.as_deref(), Some("#top?not-a-query"));
+
+            let resolution = r.resolve("#utils/format?inline", s).unwrap();
+            assert_eq!(resolution.path, Path::new("/app/src/utils/format.js"));
+            assert_eq!(resolution.query.as_deref(), Some("?inline"));
+
+            let resolution = r.resolve("./logo.svg", s).unwrap();
+            assert_eq!(resolution.query, None);
+            assert_eq!(resolution.fragment, None);
+
+            assert!(matches!(
+                r.resolve("./missing.svg?raw", s),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn package() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Browser);
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve("exports/feature", s).unwrap().package,
+                Some(PackageInfo {
+                    package_json: "/app/node_modules/exports/package.json".into(),
+                    name: Some(String::from("exports")),
+                    version: Some(String::from("1.2.3")),
+                })
+            );
+
+            let package = r.resolve("./logo.svg", s).unwrap().package.unwrap();
+            assert_eq!(package.package_json, Path::new("/app/package.json"));
+            assert_eq!(package.name.as_deref(), Some("app"));
+            assert_eq!(package.version, None);
+
+            // The package.json of the app is outside of `node_modules`
+            assert_eq!(r.resolve("no-package-json", s).unwrap().package, None);
+
+            let r = resolver(TargetEnv::Node);
+            assert_eq!(r.resolve("fs", s).unwrap().package, None);
+        });
+    }
+
+    #[test]
+    fn matched_by() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Browser);
+            let s = Path::new("/app/src/index.js");
+            let matched_by = |specifier: &str| r.resolve(specifier, s).unwrap().matched_by;
+
+            assert_eq!(matched_by("./logo.svg"), Some(MatchedBy::ExactPath));
+            assert_eq!(
+                matched_by("./utils/format"),
+                Some(MatchedBy::Extension(Extensions::Js))
+            );
+            assert_eq!(matched_by("./components"), Some(MatchedBy::IndexFile));
+            assert_eq!(
+                matched_by("main"),
+                Some(MatchedBy::MainField(MainFields::Main))
+            );
+            assert_eq!(
+                matched_by("exports/feature"),
+                Some(MatchedBy::ExportsKey(String::from("./*")))
+            );
+            assert_eq!(
+                matched_by("#utils/format"),
+                Some(MatchedBy::ImportsKey(String::from("#utils/*")))
+            );
+            assert_eq!(
+                matched_by("@/utils/format"),
+                Some(MatchedBy::TSConfigPaths(String::from("@/*")))
+            );
+            assert_eq!(
+                matched_by("src/logo.svg"),
+                Some(MatchedBy::TSConfigPaths(String::from("*")))
+            );
+
+            let r = resolver(TargetEnv::Node);
+            assert_eq!(r.resolve("fs", s).unwrap().matched_by, None);
+        });
+    }
+
+    #[test]
+    fn trace() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Browser);
+            let s = Path::new("/app/src/index.js");
+
+            let (resolution, trace) = r.resolve_with_trace("exports/feature", s).unwrap();
+            assert_eq!(
+                resolution.path,
+                Path::new("/app/node_modules/exports/lib/feature.js")
+            );
+            assert_eq!(
+                trace.steps,
+                vec![
+                    TraceStep::TSConfig("/app/tsconfig.json".into()),
+                    TraceStep::TSConfigPaths {
+                        key: String::from("*"),
+                        paths: vec![String::from("/app/exports/feature")],
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature.tsx".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature.ts".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature.jsx".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature.js".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature.css".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature.json".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature/index".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature/index.tsx".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature/index.ts".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature/index.jsx".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature/index.js".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature/index.css".into(),
+                        found: false,
+                    },
+                    TraceStep::File {
+                        path: "/app/exports/feature/index.json".into(),
+                        found: false,
+                    },
+                    TraceStep::NodeModules("/app/node_modules".into()),
+                    TraceStep::MatchExports {
+                        package_json: "/app/node_modules/exports/package.json".into(),
+                        subpath: String::from("./feature"),
+                    },
+                    TraceStep::MatchedKey(String::from("./*")),
+                    TraceStep::Target(String::from("./lib/*.js")),
+                ]
+            );
+
+            // A plain `resolve` does not trace
+            match r.resolve("./missing", s) {
+                Err(EsResolverError::ModuleNotFound { trace, .. }) => {
+                    assert!(trace.steps.is_empty())
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+
+            match r.resolve_with_trace("main/missing", s) {
+                Err(EsResolverError::ModuleNotFound { trace, .. }) => {
+                    assert!(trace
+                        .steps
+                        .contains(&TraceStep::NodeModules("/app/node_modules".into())));
+                    assert!(trace.steps.contains(&TraceStep::File {
+                        path: "/app/node_modules/main/missing.js".into(),
+                        found: false,
+                    }));
+                    assert!(trace
+                        .to_string()
+                        .contains("/app/node_modules/main/missing.js does not exist\n"));
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        });
+    }
+}
@@ -0,0 +1,377 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::test_util::{self, with_tracing};
+    use es_resolve::*;
+
+    fn resolver(env: TargetEnv) -> Resolver<MemoryFileSystem> {
+        resolver_with(env, |_| {})
+    }
+
+    fn resolver_with(
+        env: TargetEnv,
+        configure: impl FnOnce(&mut EsResolveOptions),
+    ) -> Resolver<MemoryFileSystem> {
+        test_util::resolver(
+            env,
+            MemoryFileSystem::from_json(
+                r##"
+                {
+                    "/app": {
+                        "src/index.js": "",
+                        "src/utils.js": "",
+                        "src/components/index.js": "",
+                        "src/theme.css": "",
+                        "src/logo.svg": "",
+                        "src/typed.ts": "",
+                        "src/lib/package.json": { "main": "./main.js" },
+                        "src/lib/main.js": "",
+                        "node_modules/dual": {
+                            "package.json": {
+                                "exports": {
+                                    ".": {
+                                        "style": "./style.css",
+                                        "import": "./index.mjs",
+                                        "require": "./index.cjs"
+                                    }
+                                }
+                            },
+                            "style.css": "",
+                            "index.mjs": "",
+                            "index.cjs": ""
+                        },
+                        "node_modules/legacy": {
+                            "package.json": { "main": "./lib/main" },
+                            "lib/main.js": "",
+                            "lib/util.js": ""
+                        },
+                        "node_modules/theme.css/index.css": ""
+                    }
+                }
+                "##,
+            )
+            .unwrap(),
+            configure,
+        )
+    }
+
+    #[test]
+    fn conditions() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/index.js");
+
+            for env in [TargetEnv::Node, TargetEnv::Browser] {
+                let r = resolver(env);
+
+                assert_eq!(
+                    r.resolve_with_kind("dual", s, ResolveKind::Import)
+                        .unwrap()
+                        .path,
+                    Path::new("/app/node_modules/dual/index.mjs")
+                );
+                assert_eq!(
+                    r.resolve_with_kind("dual", s, ResolveKind::DynamicImport)
+                        .unwrap()
+                        .path,
+                    Path::new("/app/node_modules/dual/index.mjs")
+                );
+                assert_eq!(
+                    r.resolve_with_kind("dual", s, ResolveKind::Require)
+                        .unwrap()
+                        .path,
+                    Path::new("/app/node_modules/dual/index.cjs")
+                );
+                assert_eq!(
+                    r.resolve_with_kind("dual", s, ResolveKind::CssImport)
+                        .unwrap()
+                        .path,
+                    Path::new("/app/node_modules/dual/style.css")
+                );
+            }
+
+            // Without a kind, the conditions of the options apply
+            assert_eq!(
+                resolver(TargetEnv::Node).resolve("dual", s).unwrap().path,
+                Path::new("/app/node_modules/dual/index.cjs")
+            );
+        });
+    }
+
+    #[test]
+    fn node_esm() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Node);
+            let s = Path::new("/app/src/index.js");
+
+            assert!(matches!(
+                r.resolve_with_kind("./utils", s, ResolveKind::Import),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+            assert!(matches!(
+                r.resolve_with_kind("./components", s, ResolveKind::Import),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+            assert!(matches!(
+                r.resolve_with_kind("legacy/lib/util", s, ResolveKind::Import),
+                Err(EsResolverError::ModuleNotFound { .. })
+            ));
+            assert_eq!(
+                r.resolve_with_kind("./utils.js", s, ResolveKind::Import)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/utils.js")
+            );
+            assert_eq!(
+                r.resolve_with_kind("legacy/lib/util.js", s, ResolveKind::Import)
+                    .unwrap()
+                    .path,
+                Path::new("/app/node_modules/legacy/lib/util.js")
+            );
+            // The main field of a package is still probed
+            assert_eq!(
+                r.resolve_with_kind("legacy", s, ResolveKind::Import)
+                    .unwrap()
+                    .path,
+                Path::new("/app/node_modules/legacy/lib/main.js")
+            );
+
+            // CommonJS rules for `require`
+            assert_eq!(
+                r.resolve_with_kind("./utils", s, ResolveKind::Require)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/utils.js")
+            );
+            assert_eq!(
+                r.resolve_with_kind("./components", s, ResolveKind::Require)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/components/index.js")
+            );
+
+            // Bundlers do not follow the rules of Node ESM
+            assert_eq!(
+                resolver(TargetEnv::Browser)
+                    .resolve_with_kind("./utils", s, ResolveKind::Import)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/utils.js")
+            );
+        });
+    }
+
+    #[test]
+    fn stylesheets() {
+        with_tracing(|| {
+            let r = resolver(TargetEnv::Browser);
+            let s = Path::new("/app/src/index.js");
+
+            assert_eq!(
+                r.resolve_with_kind("theme.css", s, ResolveKind::CssImport)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/theme.css")
+            );
+            assert_eq!(
+                r.resolve_with_kind("logo.svg", s, ResolveKind::Url)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/logo.svg")
+            );
+            // Falls back to node_modules
+            assert_eq!(
+                r.resolve_with_kind("dual", s, ResolveKind::CssImport)
+                    .unwrap()
+                    .path,
+                Path::new("/app/node_modules/dual/style.css")
+            );
+            assert_eq!(
+                r.resolve_with_kind("theme.css", s, ResolveKind::Import)
+                    .unwrap()
+                    .path,
+                Path::new("/app/node_modules/theme.css/index.css")
+            );
+        });
+    }
+
+    #[test]
+    fn fully_specified() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/index.js");
+            let suggestions = |r: &Resolver<MemoryFileSystem>, specifier: &str| match r
+                .resolve_with_kind(specifier, s, ResolveKind::Import)
+            {
+                Err(EsResolverError::ModuleNotFound { suggestions, .. }) => suggestions,
+                other => panic!("unexpected {:?}", other),
+            };
+
+            let r = resolver(TargetEnv::Node);
+
+            assert_eq!(
+                suggestions(&r, "./utils"),
+                vec![Suggestion::FullySpecified(String::from("./utils.js"))]
+            );
+            assert_eq!(
+                suggestions(&r, "./components/"),
+                vec![Suggestion::FullySpecified(String::from(
+                    "./components/index.js"
+                ))]
+            );
+            assert_eq!(
+                suggestions(&r, "./lib"),
+                vec![Suggestion::FullySpecified(String::from("./lib/main.js"))]
+            );
+            assert_eq!(
+                r.resolve_with_kind("./utils", s, ResolveKind::Import)
+                    .unwrap_err()
+                    .to_string(),
+                "Cannot resolve \"./utils\" from /app/src/index.js, which must be fully specified \
+                 with the extension. Did you mean \"./utils.js\"?"
+            );
+
+            // The extension is not rewritten unless configured
+            assert!(r
+                .resolve_with_kind("./typed.js", s, ResolveKind::Import)
+                .is_err());
+            assert_eq!(
+                r.resolve_with_kind("./typed.js", s, ResolveKind::Require)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/typed.ts")
+            );
+
+            let r = resolver_with(TargetEnv::Node, |options| {
+                options.fully_specified_rewrites = true;
+            });
+
+            assert_eq!(
+                r.resolve_with_kind("./typed.js", s, ResolveKind::Import)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/typed.ts")
+            );
+        });
+
+        with_tracing(|| {
+            let r = resolver_with(TargetEnv::Browser, |options| {
+                options.fully_specified = true;
+            });
+            let s = Path::new("/app/src/index.js");
+
+            assert!(r.resolve("./utils", s).is_err());
+            assert_eq!(
+                r.resolve("./utils.js", s).unwrap().path,
+                Path::new("/app/src/utils.js")
+            );
+            // `exports` are unaffected
+            assert_eq!(
+                r.resolve("dual", s).unwrap().path,
+                Path::new("/app/node_modules/dual/index.mjs")
+            );
+        });
+    }
+
+    #[test]
+    fn tsconfig_extends() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/index.ts");
+            let resolver = || {
+                test_util::resolver(
+                    TargetEnv::Node,
+                    MemoryFileSystem::from_json(
+                        r##"
+                        {
+                            "/app": {
+                                "src/index.ts": "",
+                                "tsconfig.json": { "extends": "shared-config" },
+                                "node_modules/shared-config": {
+                                    "package.json": {
+                                        "exports": {
+                                            ".": {
+                                                "import": "./esm.json",
+                                                "require": "./cjs.json"
+                                            }
+                                        }
+                                    },
+                                    "esm.json": {
+                                        "compilerOptions": { "baseUrl": "./esm", "paths": { "@/*": ["*"] } }
+                                    },
+                                    "cjs.json": {
+                                        "compilerOptions": { "baseUrl": "./cjs", "paths": { "@/*": ["*"] } }
+                                    },
+                                    "esm/utils.js": "",
+                                    "cjs/utils.js": ""
+                                }
+                            }
+                        }
+                        "##,
+                    )
+                    .unwrap(),
+                    |_| {},
+                )
+            };
+
+            // The tsconfig is shared by every kind, so its `extends` follows the conditions of the options
+            for kinds in [
+                [ResolveKind::Import, ResolveKind::Require],
+                [ResolveKind::Require, ResolveKind::Import],
+            ] {
+                let r = resolver();
+
+                for kind in kinds {
+                    assert_eq!(
+                        r.resolve_with_kind("@/utils.js", s, kind).unwrap().path,
+                        Path::new("/app/node_modules/shared-config/cjs/utils.js")
+                    );
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn fully_specified_tsconfig_extends() {
+        with_tracing(|| {
+            let s = Path::new("/app/src/index.ts");
+            let resolver = |configure: fn(&mut EsResolveOptions)| {
+                test_util::resolver(
+                    TargetEnv::Node,
+                    MemoryFileSystem::from_json(
+                        r##"
+                        {
+                            "/app": {
+                                "src/index.ts": "",
+                                "src/utils.ts": "",
+                                "tsconfig.json": { "extends": "./tsconfig.base" },
+                                "tsconfig.base.json": {
+                                    "compilerOptions": { "baseUrl": "./src", "paths": { "@/*": ["*"] } }
+                                }
+                            }
+                        }
+                        "##,
+                    )
+                    .unwrap(),
+                    configure,
+                )
+            };
+
+            let r = resolver(|_| {});
+
+            assert_eq!(
+                r.resolve_with_kind("@/utils.ts", s, ResolveKind::Import)
+                    .unwrap()
+                    .path,
+                Path::new("/app/src/utils.ts")
+            );
+
+            let r = resolver(|options| options.fully_specified = true);
+
+            assert_eq!(
+                r.resolve("@/utils.ts", s).unwrap().path,
+                Path::new("/app/src/utils.ts")
+            );
+        });
+    }
+}
@@ -0,0 +1,149 @@
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::test_util::{self, with_tracing, RecordingFileSystem};
+    use es_resolve::*;
+
+    fn fs() -> MemoryFileSystem {
+        MemoryFileSystem::from_json(
+            r##"
+            {
+                "/app": {
+                    "tsconfig.json": {
+                        "compilerOptions": { "baseUrl": ".", "paths": { "@/*": ["src/*"] } }
+                    },
+                    "src/index.js": "",
+                    "src/utils.ts": "",
+                    "src/Button.tsx": "",
+                    "src/legacy.mjs": "",
+                    "node_modules": {
+                        "react": { "index.js": "" },
+                        "@emotion/styled": { "index.js": "" },
+                        "pkg": {
+                            "package.json": {
+                                "exports": { ".": "./index.js", "./feature": "./feature.js" }
+                            },
+                            "index.js": "",
+                            "feature.js": ""
+                        }
+                    }
+                }
+            }
+            "##,
+        )
+        .unwrap()
+    }
+
+    fn resolver() -> Resolver<MemoryFileSystem> {
+        test_util::resolver(TargetEnv::Browser, fs(), |options| {
+            options.suggestions = true
+        })
+    }
+
+    fn suggestions(r: &Resolver<MemoryFileSystem>, specifier: &str) -> Vec<Suggestion> {
+        match r.resolve(specifier, Path::new("/app/src/index.js")) {
+            Err(EsResolverError::ModuleNotFound { suggestions, .. }) => suggestions,
+            Err(EsResolverError::PackagePathNotExported { suggestions, .. }) => suggestions,
+            other => panic!("{} resolves to {:?}", specifier, other),
+        }
+    }
+
+    #[test]
+    fn similar_files() {
+        with_tracing(|| {
+            let r = resolver();
+
+            assert_eq!(
+                suggestions(&r, "./utlis"),
+                vec![Suggestion::SimilarFile("/app/src/utils.ts".into())]
+            );
+            assert_eq!(
+                suggestions(&r, "./button"),
+                vec![Suggestion::CaseMismatch("/app/src/Button.tsx".into())]
+            );
+            // An extension that is not tried
+            assert_eq!(
+                suggestions(&r, "./legacy"),
+                vec![Suggestion::SimilarFile("/app/src/legacy.mjs".into())]
+            );
+            // Through a path alias
+            assert_eq!(
+                suggestions(&r, "@/utlis"),
+                vec![Suggestion::SimilarFile("/app/src/utils.ts".into())]
+            );
+            assert_eq!(suggestions(&r, "./completely-different"), vec![]);
+        });
+    }
+
+    #[test]
+    fn similar_packages() {
+        with_tracing(|| {
+            let r = resolver();
+
+            assert_eq!(
+                suggestions(&r, "raect"),
+                vec![Suggestion::SimilarPackage {
+                    name: String::from("react"),
+                    path: "/app/node_modules/react".into(),
+                }]
+            );
+            assert_eq!(
+                suggestions(&r, "@emotion/stlyed"),
+                vec![Suggestion::SimilarPackage {
+                    name: String::from("@emotion/styled"),
+                    path: "/app/node_modules/@emotion/styled".into(),
+                }]
+            );
+        });
+    }
+
+    #[test]
+    fn similar_exports() {
+        with_tracing(|| {
+            let r = resolver();
+
+            assert_eq!(
+                suggestions(&r, "pkg/featuer"),
+                vec![Suggestion::SimilarExport {
+                    package_json: "/app/node_modules/pkg/package.json".into(),
+                    subpath: String::from("./feature"),
+                }]
+            );
+
+            match r.resolve_with_trace("./utlis", Path::new("/app/src/index.js")) {
+                Err(EsResolverError::ModuleNotFound {
+                    trace, suggestions, ..
+                }) => {
+                    assert!(!trace.steps.is_empty());
+                    assert_eq!(
+                        suggestions,
+                        vec![Suggestion::SimilarFile("/app/src/utils.ts".into())]
+                    );
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn off_by_default() {
+        with_tracing(|| {
+            let r = test_util::resolver(TargetEnv::Browser, RecordingFileSystem::new(fs()), |_| {});
+            let s = Path::new("/app/src/index.js");
+
+            for specifier in ["./utlis", "@emotion/stlyed", "pkg/featuer"] {
+                match r.resolve(specifier, s) {
+                    Err(EsResolverError::ModuleNotFound { suggestions, .. })
+                    | Err(EsResolverError::PackagePathNotExported { suggestions, .. }) => {
+                        assert!(suggestions.is_empty())
+                    }
+                    other => panic!("{} resolves to {:?}", specifier, other),
+                }
+            }
+            assert!(r.file_system().listed.lock().unwrap().is_empty());
+        });
+    }
+}
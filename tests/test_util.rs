@@ -1,4 +1,13 @@
+// Each test binary only uses some of these.
+#![allow(dead_code)]
 
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use es_resolve::{EsResolveOptions, FileMetadata, FileSystem, Resolver, TargetEnv};
 use tracing::Level;
 
 pub fn with_tracing(f: fn() -> ()) {
@@ -14,3 +23,83 @@ pub fn with_tracing(f: fn() -> ()) {
         tracing::debug!("test tracing ends");
     });
 }
+
+/// A resolver for `env` over `fs`, with the default options of `env` changed by `configure`.
+pub fn resolver<F: FileSystem>(
+    env: TargetEnv,
+    fs: F,
+    configure: impl FnOnce(&mut EsResolveOptions),
+) -> Resolver<F> {
+    let mut options = EsResolveOptions::default_for(env.clone());
+    configure(&mut options);
+
+    Resolver::with_file_system(env, options, fs)
+}
+
+/// Records the files read from `inner`, the paths it canonicalizes and the directories it lists,
+/// to check that no I/O bypasses the [`FileSystem`] and what the resolver caches.
+#[derive(Debug, Default)]
+pub struct RecordingFileSystem<F> {
+    pub inner: F,
+    pub reads: Mutex<Vec<PathBuf>>,
+    pub canonicalized: Mutex<Vec<PathBuf>>,
+    pub listed: Mutex<Vec<PathBuf>>,
+}
+
+impl<F> RecordingFileSystem<F> {
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            reads: Mutex::default(),
+            canonicalized: Mutex::default(),
+            listed: Mutex::default(),
+        }
+    }
+
+    /// How many times `path` was read.
+    pub fn reads_of(&self, path: impl AsRef<Path>) -> usize {
+        count(&self.reads, path.as_ref())
+    }
+
+    /// How many times `path` was canonicalized.
+    pub fn canonicalized_of(&self, path: impl AsRef<Path>) -> usize {
+        count(&self.canonicalized, path.as_ref())
+    }
+}
+
+fn count(paths: &Mutex<Vec<PathBuf>>, path: &Path) -> usize {
+    paths.lock().unwrap().iter().filter(|p| *p == path).count()
+}
+
+impl<F: FileSystem> FileSystem for RecordingFileSystem<F> {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.reads.lock().unwrap().push(path.to_path_buf());
+        self.inner.read_to_string(path)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        self.inner.metadata(path)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.inner.read_link(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.canonicalized.lock().unwrap().push(path.to_path_buf());
+        self.inner.canonicalize(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        self.listed.lock().unwrap().push(path.to_path_buf());
+        self.inner.read_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.inner.is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.inner.is_dir(path)
+    }
+}